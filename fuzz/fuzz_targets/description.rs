@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|description: &str| {
+    let _ = chapters::from_description(description);
+    let _ = chapters::from_description_verbose(description);
+    let _ = chapters::from_description_html(description);
+
+    let options = chapters::DescriptionOptions {
+        allow_long_minutes: true,
+        allow_out_of_range: true,
+        allow_fractional_seconds: true,
+        ..Default::default()
+    };
+    let _ = chapters::from_description_with_options(description, &options);
+});
@@ -0,0 +1,171 @@
+use crate::Chapter;
+use chrono::Duration;
+
+/// Reads [chapters](crate::Chapter) from an [Audacity](https://www.audacityteam.org) label track
+/// export.
+///
+/// Each line has a start time, an end time, and a label, separated by tabs, with times in seconds
+/// using `.` as the decimal point (e.g., `30.500000`). The label becomes the chapter title. Point
+/// labels, whose start and end are the same, have no [end](crate::Chapter::end). Lines of spectral
+/// selections, which start with `\`, are skipped.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let labels = "0.000000\t30.500000\tIntro
+/// 30.500000\t30.500000\tBaboons
+/// ";
+///
+/// let chapters = chapters::from_audacity_labels(labels.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::milliseconds(30_500)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::milliseconds(30_500),
+///             title: Some("Baboons".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_audacity_labels<R: std::io::Read>(mut reader: R) -> Result<Vec<Chapter>, String> {
+    let mut labels = String::new();
+    reader
+        .read_to_string(&mut labels)
+        .map_err(|e| format!("Error reading labels: {}", e))?;
+
+    let mut chapters = Vec::new();
+    for line in labels.trim_start_matches('\u{feff}').lines() {
+        if line.trim().is_empty() || line.starts_with('\\') {
+            continue;
+        }
+
+        let mut fields = line.splitn(3, '\t');
+        let (Some(start), Some(end)) = (fields.next(), fields.next()) else {
+            return Err(format!("Label line `{}` has too few columns", line));
+        };
+        let title = fields.next().unwrap_or_default().trim();
+
+        let start = parse_seconds(start)?;
+        let end = parse_seconds(end)?;
+        if end < start {
+            return Err(format!("Label line `{}` ends before it starts", line));
+        }
+
+        chapters.push(Chapter {
+            start,
+            end: if end > start { Some(end) } else { None },
+            title: if title.is_empty() {
+                None
+            } else {
+                Some(title.to_string())
+            },
+            ..Default::default()
+        });
+    }
+
+    Ok(chapters)
+}
+
+/// Writes [chapters](crate::Chapter) as an [Audacity](https://www.audacityteam.org) label track,
+/// which can be imported into an editing session.
+///
+/// Each chapter becomes a `start\tend\ttitle` line with times in seconds to three decimals.
+/// Chapters without an [end](crate::Chapter::end) are written as point labels, i.e., with the
+/// start time in the end column, rather than extended to the next chapter, so that reading the
+/// labels back with [from_audacity_labels] gives the same chapters. Tabs and line breaks in titles
+/// are replaced with spaces. Returns an error if a time is negative.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         end: Some(Duration::milliseconds(30_500)),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::milliseconds(30_500),
+///         title: Some("Baboons\tand\nSteve Jobs".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let labels = chapters::to_audacity_labels(&chapters).expect("Failed to write labels");
+///
+/// assert_eq!(
+///     labels,
+///     "0.000\t30.500\tIntro\n30.500\t30.500\tBaboons and Steve Jobs\n"
+/// );
+/// # }
+/// ```
+pub fn to_audacity_labels(chapters: &[Chapter]) -> Result<String, String> {
+    let mut labels = String::new();
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        let start = chapter.start;
+        let end = chapter.end.unwrap_or(start);
+        if start < Duration::zero() || end < Duration::zero() {
+            return Err(format!("Chapter {} has a negative time", i));
+        }
+
+        let title: String = chapter
+            .title
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .map(|c| {
+                if c == '\t' || c == '\r' || c == '\n' {
+                    ' '
+                } else {
+                    c
+                }
+            })
+            .collect();
+        labels.push_str(&format!(
+            "{}\t{}\t{}\n",
+            format_seconds(start),
+            format_seconds(end),
+            title
+        ));
+    }
+
+    Ok(labels)
+}
+
+/// Formats a non-negative duration as seconds with three decimals, e.g., "30.500".
+fn format_seconds(duration: Duration) -> String {
+    let milliseconds = duration.num_milliseconds();
+    format!("{}.{:03}", milliseconds / 1000, milliseconds % 1000)
+}
+
+/// Parses a non-negative number of seconds, e.g., "30.500000", rounding it to milliseconds.
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid time `{}`", s);
+
+    let seconds: f64 = s.trim().parse().map_err(|_| invalid())?;
+    // Also rejects times that don't fit into a duration.
+    if !(0.0..1e15).contains(&seconds) {
+        return Err(invalid());
+    }
+
+    Ok(Duration::milliseconds((seconds * 1000.0).round() as i64))
+}
@@ -0,0 +1,239 @@
+use crate::Chapter;
+use chrono::Duration;
+
+/// Number of CUE frames per second.
+const FRAMES_PER_SECOND: i64 = 75;
+
+/// Reads [chapters](crate::Chapter) from a [CUE sheet](https://en.wikipedia.org/wiki/Cue_sheet_(computing)).
+///
+/// Every `TRACK` becomes a chapter, with its `INDEX 01` (in `MM:SS:FF` format, where a frame is
+/// 1/75 of a second) used as the start time and its `TITLE` as the title. File-level commands,
+/// such as the album `TITLE` or `PERFORMER`, are ignored.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let cue = r#"PERFORMER "The Podcast"
+/// TITLE "Episode 1"
+/// FILE "episode-1.mp3" MP3
+///   TRACK 01 AUDIO
+///     TITLE "Intro"
+///     INDEX 01 00:00:00
+///   TRACK 02 AUDIO
+///     TITLE "Baboons"
+///     PERFORMER "The Podcast"
+///     INDEX 01 05:04:60
+///   TRACK 03 AUDIO
+///     TITLE "Steve Jobs"
+///     INDEX 01 72:15:00
+/// "#;
+///
+/// let chapters = chapters::from_cue(cue.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800),
+///             title: Some("Baboons".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(72) + Duration::seconds(15),
+///             title: Some("Steve Jobs".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_cue<R: std::io::Read>(mut reader: R) -> Result<Vec<Chapter>, String> {
+    let mut cue = String::new();
+    reader
+        .read_to_string(&mut cue)
+        .map_err(|e| format!("Error reading CUE sheet: {}", e))?;
+
+    // Each track is a (number, title, start) triple.
+    let mut tracks: Vec<(String, Option<String>, Option<Duration>)> = Vec::new();
+
+    for line in cue.lines().map(|line| line.trim()) {
+        let (command, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let arguments = arguments.trim();
+
+        match command.to_uppercase().as_str() {
+            "TRACK" => {
+                let number = arguments.split_whitespace().next().unwrap_or_default();
+                tracks.push((number.to_string(), None, None));
+            }
+            "TITLE" => {
+                if let Some(track) = tracks.last_mut() {
+                    track.1 = Some(unquote(arguments));
+                }
+            }
+            "INDEX" => {
+                let mut parts = arguments.split_whitespace();
+                if let (Some("01"), Some(timestamp), Some(track)) =
+                    (parts.next(), parts.next(), tracks.last_mut())
+                {
+                    track.2 = Some(parse_cue_timestamp(timestamp)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tracks
+        .into_iter()
+        .map(|(number, title, start)| {
+            Ok(Chapter {
+                start: start.ok_or_else(|| format!("Track {} is missing `INDEX 01`", number))?,
+                title,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Writes [chapters](crate::Chapter) to a [CUE sheet](https://en.wikipedia.org/wiki/Cue_sheet_(computing)) for the audio file `audio_filename`.
+///
+/// Every chapter becomes a `TRACK` with its title in `TITLE` and its start time in `INDEX 01`
+/// (in `MM:SS:FF` format, where a frame is 1/75 of a second). Quotes inside titles and the file
+/// name are escaped with a backslash. Since CUE sheets are limited to 99 tracks, an error is
+/// returned for more chapters.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800),
+///         title: Some("The \"Baboons\"".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let cue = chapters::to_cue(&chapters, "episode-1.mp3").expect("Failed to write chapters");
+///
+/// assert_eq!(
+///     cue,
+///     r#"FILE "episode-1.mp3" MP3
+///   TRACK 01 AUDIO
+///     TITLE "Intro"
+///     INDEX 01 00:00:00
+///   TRACK 02 AUDIO
+///     TITLE "The \"Baboons\""
+///     INDEX 01 05:04:60
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_cue(chapters: &[Chapter], audio_filename: &str) -> Result<String, String> {
+    if chapters.len() > 99 {
+        return Err(format!(
+            "CUE sheets can have at most 99 tracks, but there are {} chapters",
+            chapters.len()
+        ));
+    }
+
+    let mut cue = format!("FILE {} MP3\n", quote(audio_filename));
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        cue.push_str(&format!("  TRACK {:02} AUDIO\n", i + 1));
+        if let Some(title) = &chapter.title {
+            cue.push_str(&format!("    TITLE {}\n", quote(title)));
+        }
+        cue.push_str(&format!(
+            "    INDEX 01 {}\n",
+            format_cue_timestamp(chapter.start)?
+        ));
+    }
+
+    Ok(cue)
+}
+
+/// Puts quotes around a CUE string, escaping any quotes within it.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+/// Removes the quotes around a CUE string, unescaping any quotes within it.
+fn unquote(s: &str) -> String {
+    match s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(s) => s.replace("\\\"", "\""),
+        None => s.to_string(),
+    }
+}
+
+/// Parses a CUE timestamp in `MM:SS:FF` format.
+fn parse_cue_timestamp(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid CUE timestamp `{}`", s);
+
+    let parts = s
+        .split(':')
+        .map(|part| part.parse::<i64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (minutes, seconds, frames) = match parts[..] {
+        [minutes, seconds, frames]
+            if minutes >= 0
+                && (0..60).contains(&seconds)
+                && (0..FRAMES_PER_SECOND).contains(&frames) =>
+        {
+            (minutes, seconds, frames)
+        }
+        _ => return Err(invalid()),
+    };
+
+    Duration::try_minutes(minutes)
+        .and_then(|duration| duration.checked_add(&Duration::seconds(seconds)))
+        .and_then(|duration| {
+            duration.checked_add(&Duration::milliseconds(
+                (frames * 1000 + FRAMES_PER_SECOND / 2) / FRAMES_PER_SECOND,
+            ))
+        })
+        .ok_or_else(invalid)
+}
+
+/// Formats a duration as a CUE timestamp in `MM:SS:FF` format, rounding to the nearest frame.
+fn format_cue_timestamp(duration: Duration) -> Result<String, String> {
+    if duration < Duration::zero() {
+        return Err(format!(
+            "Negative chapter start time ({} ms) cannot be written to a CUE sheet",
+            duration.num_milliseconds()
+        ));
+    }
+
+    let frames = duration
+        .num_milliseconds()
+        .checked_mul(FRAMES_PER_SECOND)
+        .and_then(|frames| frames.checked_add(500))
+        .ok_or_else(|| {
+            format!(
+                "Chapter start time ({} ms) is too large to be written to a CUE sheet",
+                duration.num_milliseconds()
+            )
+        })?
+        / 1000;
+    let minutes = frames / (60 * FRAMES_PER_SECOND);
+    let seconds = frames / FRAMES_PER_SECOND % 60;
+    let frames = frames % FRAMES_PER_SECOND;
+
+    Ok(format!("{minutes:02}:{seconds:02}:{frames:02}"))
+}
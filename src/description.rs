@@ -0,0 +1,1004 @@
+use crate::Chapter;
+use chrono::Duration;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Punctuation that may separate a timestamp from the title in episode descriptions.
+const DESCRIPTION_SEPARATORS: [char; 8] = ['.', '!', '?', '-', '–', '—', ':', '|'];
+
+/// Timestamp format used in episode descriptions.
+#[derive(Debug, Clone, PartialEq)]
+enum TimestampType {
+    /// MM:SS format, e.g., "12:34" or "12:34.5"
+    MmSs,
+    /// HH:MM:SS format, e.g., "01:23:45" or "1:23:45"
+    HhMmSs,
+    /// MM:SS format within parentheses, e.g., "(12:34)"
+    MmSsParentheses,
+    /// HH:MM:SS format within parentheses, e.g., "(01:23:45)" or "(1:23:45)"
+    HhMmSsParentheses,
+}
+
+/// Placement of the timestamp on a line of an episode description.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TimestampPosition {
+    /// Timestamp before the title, e.g., "05:04 - Baboons"
+    Leading,
+    /// Timestamp after the title, e.g., "Baboons - 05:04" or "Baboons (05:04)"
+    Trailing,
+}
+
+impl TimestampType {
+    /// Returns the public counterpart of this type.
+    fn format(&self) -> TimestampFormat {
+        match self {
+            Self::MmSs => TimestampFormat::MmSs,
+            Self::HhMmSs => TimestampFormat::HhMmSs,
+            Self::MmSsParentheses => TimestampFormat::MmSsParentheses,
+            Self::HhMmSsParentheses => TimestampFormat::HhMmSsParentheses,
+        }
+    }
+
+    const ALL: [Self; 4] = [
+        Self::MmSs,
+        Self::HhMmSs,
+        Self::MmSsParentheses,
+        Self::HhMmSsParentheses,
+    ];
+
+    fn regex_pattern(&self, options: &DescriptionOptions) -> String {
+        // Minutes and seconds below 60, unless out-of-range values are allowed.
+        let sexagesimal = if options.allow_out_of_range {
+            r"\d{2}"
+        } else {
+            r"[0-5]\d"
+        };
+        let leading_minutes = if options.allow_long_minutes {
+            r"\d{2,}"
+        } else {
+            sexagesimal
+        };
+        let fraction = if options.allow_fractional_seconds {
+            r"(?:\.(?P<fraction>\d{1,3}))?"
+        } else {
+            ""
+        };
+
+        match self {
+            Self::MmSs => {
+                format!(r"(?P<minutes>{leading_minutes}):(?P<seconds>{sexagesimal}){fraction}")
+            }
+            Self::HhMmSs => format!(
+                r"(?P<hours>\d{{1,2}}):(?P<minutes>{sexagesimal}):(?P<seconds>{sexagesimal}){fraction}"
+            ),
+            Self::MmSsParentheses => {
+                format!(r"\((?P<minutes>{leading_minutes}):(?P<seconds>{sexagesimal}){fraction}\)")
+            }
+            Self::HhMmSsParentheses => format!(
+                r"\((?P<hours>\d{{1,2}}):(?P<minutes>{sexagesimal}):(?P<seconds>{sexagesimal}){fraction}\)"
+            ),
+        }
+    }
+
+    fn line_regex_pattern(
+        &self,
+        position: TimestampPosition,
+        options: &DescriptionOptions,
+    ) -> String {
+        // Combines the timestamp regex pattern with space (or a punctuation mark) and a pattern for the title before or after the timestamp.
+        // A colon must be followed by whitespace so that "01:02:03" is not read as "01:02" and "03".
+        let separator = r"(?:[.!?\-–—|\s]|:\s)+";
+        // Numbered lists put an ordinal before the timestamp, e.g., "1. 00:00 Intro" or "1) 00:00 Intro".
+        let ordinal = r"(?:\d+[.)]\s*)?";
+        match position {
+            TimestampPosition::Leading => {
+                format!(
+                    "^{ordinal}{}{separator}(?P<text>.+)$",
+                    self.regex_pattern(options)
+                )
+            }
+            TimestampPosition::Trailing => {
+                format!("^(?P<text>.+?){separator}{}$", self.regex_pattern(options))
+            }
+        }
+    }
+
+    /// Returns the compiled [line_regex_pattern](Self::line_regex_pattern). Every pattern is only
+    /// compiled once and then reused across lines and calls.
+    fn line_regex(
+        &self,
+        position: TimestampPosition,
+        options: &DescriptionOptions,
+    ) -> Result<regex::Regex, String> {
+        static CACHE: OnceLock<Mutex<HashMap<String, regex::Regex>>> = OnceLock::new();
+
+        let pattern = self.line_regex_pattern(position, options);
+        // The cache is never left half-updated, so a poisoned lock is still safe to use.
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(re) = cache.get(&pattern) {
+            return Ok(re.clone());
+        }
+        let re = regex::Regex::new(&pattern).map_err(|e| e.to_string())?;
+        cache.insert(pattern, re.clone());
+        Ok(re)
+    }
+
+    /// Finds the timestamp format and position matching the most lines. In case of a tie,
+    /// leading timestamps are preferred.
+    fn detect(lines: &[&str], options: &DescriptionOptions) -> Option<(Self, TimestampPosition)> {
+        let mut best: Option<((Self, TimestampPosition), usize)> = None;
+
+        for position in [TimestampPosition::Leading, TimestampPosition::Trailing] {
+            // regex can be expensive, so we first check if the line at least starts (or ends) with the right character.
+            let candidates: Vec<&str> = lines
+                .iter()
+                .filter(|line| {
+                    let c = match position {
+                        TimestampPosition::Leading => line.chars().next(),
+                        TimestampPosition::Trailing => line.chars().next_back(),
+                    };
+                    c.is_some_and(|c| c == '(' || c == ')' || c.is_numeric())
+                })
+                .copied()
+                .collect();
+            if candidates.is_empty() {
+                continue;
+            }
+
+            for timestamp_type in Self::ALL {
+                let re = match timestamp_type.line_regex(position, options) {
+                    Ok(re) => re,
+                    Err(_) => continue,
+                };
+                let count = candidates.iter().filter(|line| re.is_match(line)).count();
+                if count > 0
+                    && best
+                        .as_ref()
+                        .is_none_or(|(_, best_count)| count > *best_count)
+                {
+                    best = Some(((timestamp_type, position), count));
+                }
+            }
+        }
+
+        best.map(|(detected, _)| detected)
+    }
+}
+
+/// Reads [chapters](crate::Chapter) from [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes).
+///
+/// Timestamps can be placed either before the chapter title (e.g., "05:04 - Baboons") or after it
+/// (e.g., "Baboons - 05:04" or "Baboons (05:04)"); the layout used by most lines is picked.
+/// Arbitrary input is safe to pass: malformed lines are skipped or reported as an error, and
+/// parsing never panics.
+///
+/// # Example:
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let description = r#"
+/// In this episode, we explore a hot new trend in fitness: "The Movement"!
+///
+/// 00:00 - The Movement
+/// 05:04 - Baboons
+/// 09:58 - Steve Jobs
+/// "#;
+///
+/// let chapters = chapters::from_description(description).expect("Failed to parse chapters");
+///
+/// assert_eq!(chapters.len(), 3);
+/// assert_eq!(chapters[1].title, Some(String::from("Baboons")));
+/// # }
+/// ```
+pub fn from_description(description: &str) -> Result<Vec<Chapter>, String> {
+    from_description_with_options(description, &DescriptionOptions::default())
+}
+
+/// Options for [from_description_with_options].
+#[derive(Debug, Clone)]
+pub struct DescriptionOptions {
+    /// Accept MM:SS timestamps with 60 or more minutes, e.g., "72:15", which long-form shows
+    /// sometimes use instead of HH:MM:SS. Disabled by default.
+    pub allow_long_minutes: bool,
+    /// Accept two-digit minutes and seconds of 60 or more, e.g., "05:75", which are carried over
+    /// into the next field ("05:75" is read as 6 minutes and 15 seconds). Disabled by default, in
+    /// which case such lines are not recognized as chapters.
+    pub allow_out_of_range: bool,
+    /// Accept fractional seconds in timestamps, e.g., "05:04.5". Enabled by default.
+    pub allow_fractional_seconds: bool,
+    /// Stop at the first line that does not match the timestamp format once chapters have been
+    /// found. If disabled, such lines (e.g., blank lines or prose between chapters) are skipped.
+    /// Disabled by default.
+    pub require_contiguous: bool,
+    /// Minimum number of chapters for the description to be considered to have chapters. If fewer
+    /// are found, no chapters are returned. Defaults to 0.
+    pub min_chapters: usize,
+}
+
+impl Default for DescriptionOptions {
+    fn default() -> Self {
+        Self {
+            allow_long_minutes: false,
+            allow_out_of_range: false,
+            allow_fractional_seconds: true,
+            require_contiguous: false,
+            min_chapters: 0,
+        }
+    }
+}
+
+/// Reads [chapters](crate::Chapter) from [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes) using the given options.
+///
+/// [from_description] is equivalent to calling this function with the
+/// default options.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::DescriptionOptions;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let description = r#"
+/// 00:00 - Intro
+/// 59:30 - Listener questions
+/// 72:15 - Outro
+/// "#;
+///
+/// let chapters = chapters::from_description(description).expect("Failed to parse chapters");
+/// assert_eq!(chapters.len(), 2);
+///
+/// let options = DescriptionOptions {
+///     allow_long_minutes: true,
+///     ..Default::default()
+/// };
+/// let chapters = chapters::from_description_with_options(description, &options)
+///     .expect("Failed to parse chapters");
+/// assert_eq!(chapters.len(), 3);
+/// assert_eq!(chapters[2].start, Duration::hours(1) + Duration::minutes(12) + Duration::seconds(15));
+/// # }
+/// ```
+pub fn from_description_with_options(
+    description: &str,
+    options: &DescriptionOptions,
+) -> Result<Vec<Chapter>, String> {
+    parse_description(description, options).map(|parsed| parsed.chapters)
+}
+
+/// A line of an episode description that looks like a chapter but could not be read as one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Number of the line in the description, starting at 1.
+    pub line_number: usize,
+    /// Why the line was not read as a chapter.
+    pub reason: ParseWarningReason,
+}
+
+/// Reason for a [ParseWarning].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarningReason {
+    /// The line contains something that looks like a timestamp, but the line does not match any
+    /// known chapter format, e.g., "Intro at 00:00 or so".
+    UnrecognizedTimestamp,
+    /// The line is a chapter in a different timestamp format or position than the one used by the
+    /// rest of the description, e.g., "1:05:04 - Outro" among "MM:SS - Title" lines.
+    FormatSwitched,
+}
+
+/// Reads [chapters](crate::Chapter) from [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes), reporting the lines that look like chapters but were skipped.
+///
+/// The chapters are the same as the ones returned by [from_description].
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{ParseWarning, ParseWarningReason};
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let description = r#"00:00 - Intro
+/// 05:04 - Baboons
+/// We get to Steve Jobs at 09:58 or so.
+/// 1:05:04 - Outro
+/// "#;
+///
+/// let (chapters, warnings) = chapters::from_description_verbose(description);
+///
+/// assert_eq!(chapters.len(), 2);
+/// assert_eq!(
+///     warnings,
+///     vec![
+///         ParseWarning {
+///             line_number: 3,
+///             reason: ParseWarningReason::UnrecognizedTimestamp,
+///         },
+///         ParseWarning {
+///             line_number: 4,
+///             reason: ParseWarningReason::FormatSwitched,
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_description_verbose(description: &str) -> (Vec<Chapter>, Vec<ParseWarning>) {
+    // The patterns are fixed, so compiling them cannot fail.
+    parse_description(description, &DescriptionOptions::default())
+        .map(|parsed| (parsed.chapters, parsed.warnings))
+        .unwrap_or_default()
+}
+
+/// Timestamp format of chapters in an episode description; see [detect_description_format].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// MM:SS format, e.g., "12:34" or "12:34.5".
+    MmSs,
+    /// HH:MM:SS format, e.g., "01:23:45" or "1:23:45".
+    HhMmSs,
+    /// MM:SS format within parentheses, e.g., "(12:34)".
+    MmSsParentheses,
+    /// HH:MM:SS format within parentheses, e.g., "(01:23:45)" or "(1:23:45)".
+    HhMmSsParentheses,
+}
+
+/// Detects the timestamp format of chapters in an [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes).
+///
+/// This is the format [from_description] would use, or [None] if the description has no chapters.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::TimestampFormat;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let description = r#"00:00:00 - Intro
+/// 00:05:04 - Baboons
+/// "#;
+///
+/// assert_eq!(
+///     chapters::detect_description_format(description),
+///     Some(TimestampFormat::HhMmSs)
+/// );
+/// assert_eq!(chapters::detect_description_format("No chapters here."), None);
+/// # }
+/// ```
+pub fn detect_description_format(description: &str) -> Option<TimestampFormat> {
+    let description = normalize_description(description);
+    let lines: Vec<&str> = description.lines().map(|line| line.trim()).collect();
+
+    TimestampType::detect(&lines, &DescriptionOptions::default())
+        .map(|(timestamp_type, _)| timestamp_type.format())
+}
+
+/// Chapters read from an episode description.
+#[derive(Default)]
+pub(crate) struct ParsedDescription {
+    pub(crate) chapters: Vec<Chapter>,
+    /// Zero-based index of the line each chapter was read from.
+    pub(crate) chapter_lines: Vec<usize>,
+    pub(crate) warnings: Vec<ParseWarning>,
+}
+
+pub(crate) fn parse_description(
+    description: &str,
+    options: &DescriptionOptions,
+) -> Result<ParsedDescription, String> {
+    let mut parsed = ParsedDescription::default();
+
+    let description = normalize_description(description);
+    let lines: Vec<&str> = description.lines().map(|line| line.trim()).collect();
+
+    let (timestamp_type, position) = match TimestampType::detect(&lines, options) {
+        Some(detected) => detected,
+        None => return Ok(parsed),
+    };
+    let re = timestamp_type.line_regex(position, options)?;
+
+    // Used to tell why a line was skipped.
+    let mut other_formats = Vec::new();
+    for other_position in [TimestampPosition::Leading, TimestampPosition::Trailing] {
+        for other_type in TimestampType::ALL {
+            if other_position != position || other_type != timestamp_type {
+                other_formats.push(other_type.line_regex(other_position, options)?);
+            }
+        }
+    }
+    static ANY_TIMESTAMP: OnceLock<regex::Regex> = OnceLock::new();
+    let any_timestamp = ANY_TIMESTAMP.get_or_init(|| regex::Regex::new(r"\d{1,2}:\d{2}").unwrap());
+
+    let parse_line = |line: &str| -> Option<Chapter> {
+        if let Some(captures) = re.captures(line) {
+            let start = timestamp_from_captures(&captures).ok()?;
+            let text = captures.name("text")?.as_str();
+            let is_separator = |c: char| c.is_whitespace() || DESCRIPTION_SEPARATORS.contains(&c);
+            let text = match position {
+                TimestampPosition::Leading => text.trim_start_matches(is_separator),
+                TimestampPosition::Trailing => text.trim_end_matches(is_separator),
+            };
+            Some(Chapter {
+                start,
+                end: None,
+                title: Some(text.trim().to_string()),
+                language: None,
+                image: None,
+                images: Vec::new(),
+                link: None,
+                hidden: false,
+                location: None,
+                element_id: None,
+                start_byte: None,
+                end_byte: None,
+                #[cfg(feature = "rssblue")]
+                remote_entity: None,
+            })
+        } else {
+            None
+        }
+    };
+
+    for (i, line) in lines.into_iter().enumerate() {
+        if let Some(chapter) = parse_line(line) {
+            parsed.chapters.push(chapter);
+            parsed.chapter_lines.push(i);
+            continue;
+        }
+
+        let reason = if other_formats.iter().any(|re| re.is_match(line)) {
+            Some(ParseWarningReason::FormatSwitched)
+        } else if any_timestamp.is_match(line) {
+            Some(ParseWarningReason::UnrecognizedTimestamp)
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            parsed.warnings.push(ParseWarning {
+                line_number: i + 1,
+                reason,
+            });
+        }
+
+        if options.require_contiguous && !parsed.chapters.is_empty() {
+            break;
+        }
+    }
+
+    if parsed.chapters.len() < options.min_chapters {
+        parsed.chapters.clear();
+        parsed.chapter_lines.clear();
+    }
+
+    Ok(parsed)
+}
+
+/// Cleans up text pasted from word processors so that timestamps at the start of lines are
+/// still detected: drops the byte order mark and zero-width characters. Everything else, e.g.,
+/// smart quotes in titles, is kept as is.
+fn normalize_description(description: &str) -> String {
+    description
+        .trim_start_matches('\u{FEFF}')
+        .chars()
+        .filter(|c| !matches!(c, '\u{FEFF}' | '\u{200B}' | '\u{200C}' | '\u{200D}'))
+        .collect()
+}
+
+/// Writes [chapters](crate::Chapter) to [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes).
+///
+/// Only the start time and title are used. Every chapter needs a title, so an untitled
+/// [marker](crate::Chapter::marker) results in an error naming the chapter; see
+/// [to_description_with_options] to skip such chapters or give them a placeholder title instead.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("The Movement".to_string()),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com/the-movement").unwrap(),
+///             title: None,
+///         }),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5) + Duration::seconds(4),
+///         title: Some("Baboons".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(9) + Duration::seconds(58),
+///         title: Some("Steve Jobs".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let description = chapters::to_description(&chapters).expect("Failed to write chapters");
+/// assert_eq!(
+///     description,
+///     r#"00:00 The Movement
+/// 05:04 Baboons
+/// 09:58 Steve Jobs
+/// "#
+/// );
+/// # }
+///    ```
+pub fn to_description(chapters: &[Chapter]) -> Result<String, String> {
+    to_description_with_options(chapters, &ToDescriptionOptions::default())
+}
+
+/// How fractional seconds are handled when writing timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Drop the fractional part, e.g., 05:04.6 is written as "05:04".
+    #[default]
+    Truncate,
+    /// Round to the nearest second, with halves rounded up, e.g., 05:04.5 is written as "05:05".
+    HalfUp,
+}
+
+impl Rounding {
+    fn apply(&self, duration: Duration) -> Duration {
+        match self {
+            Self::Truncate => Duration::seconds(duration.num_seconds()),
+            Self::HalfUp => Duration::seconds((duration.num_milliseconds() + 500).div_euclid(1000)),
+        }
+    }
+}
+
+/// Whether timestamps in a description share the same format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HourFormatting {
+    /// All timestamps include hours if any chapter starts at or after an hour, e.g., "00:03:00" and "01:05:04".
+    #[default]
+    Uniform,
+    /// Each timestamp includes hours only if its own chapter starts at or after an hour, e.g., "03:00" and "01:05:04".
+    PerChapter,
+}
+
+/// Whether the first field of a timestamp is zero-padded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding {
+    /// Every field has two digits, e.g., "05:04" and "01:05:04".
+    #[default]
+    ZeroPadded,
+    /// The first field has no leading zero, e.g., "5:04" and "1:05:04". The remaining fields still have two digits.
+    Unpadded,
+}
+
+/// What to do with chapters that have no title when writing a description.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MissingTitlePolicy {
+    /// Return an error naming the first untitled chapter.
+    #[default]
+    Error,
+    /// Leave untitled chapters out of the description.
+    Skip,
+    /// Use the given title, e.g., "Untitled", for untitled chapters.
+    Placeholder(String),
+}
+
+/// Options for [to_description_with_options].
+#[derive(Debug, Clone, Default)]
+pub struct ToDescriptionOptions {
+    /// How the start times are rounded to whole seconds. Defaults to [truncation](crate::Rounding::Truncate).
+    pub rounding: Rounding,
+    /// Whether hours are included in all timestamps or only where needed. Defaults to [uniform](crate::HourFormatting::Uniform).
+    pub hour_formatting: HourFormatting,
+    /// Whether the first field of each timestamp is zero-padded. Defaults to [zero-padded](crate::Padding::ZeroPadded).
+    pub padding: Padding,
+    /// What to do with chapters that have no title. Defaults to [an error](crate::MissingTitlePolicy::Error).
+    pub missing_title: MissingTitlePolicy,
+}
+
+/// Writes [chapters](crate::Chapter) to [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes) using the given options.
+///
+/// [to_description] is equivalent to calling this function with the
+/// default options.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, HourFormatting, Rounding, ToDescriptionOptions};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("The Movement".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5) + Duration::milliseconds(4600),
+///         title: Some("Baboons".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let options = ToDescriptionOptions {
+///     rounding: Rounding::HalfUp,
+///     ..Default::default()
+/// };
+/// let description = chapters::to_description_with_options(&chapters, &options)
+///     .expect("Failed to write chapters");
+/// assert_eq!(
+///     description,
+///     r#"00:00 The Movement
+/// 05:05 Baboons
+/// "#
+/// );
+///
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::minutes(3),
+///         title: Some("Baboons".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::hours(1) + Duration::minutes(5) + Duration::seconds(4),
+///         title: Some("Steve Jobs".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let options = ToDescriptionOptions {
+///     hour_formatting: HourFormatting::PerChapter,
+///     ..Default::default()
+/// };
+/// let description = chapters::to_description_with_options(&chapters, &options)
+///     .expect("Failed to write chapters");
+/// assert_eq!(
+///     description,
+///     r#"03:00 Baboons
+/// 01:05:04 Steve Jobs
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_description_with_options(
+    chapters: &[Chapter],
+    options: &ToDescriptionOptions,
+) -> Result<String, String> {
+    write_description(chapters, options, false)
+}
+
+/// Writes [chapters](crate::Chapter) to [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes), including chapter links.
+///
+/// Same as [to_description], except that chapters with a [link](crate::Chapter::link) have
+/// " - " and the link's title appended to their line, or the bare URL if the link has no title.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("The Movement".to_string()),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com/the-movement").unwrap(),
+///             title: None,
+///         }),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5) + Duration::seconds(4),
+///         title: Some("Baboons".to_string()),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com/baboons").unwrap(),
+///             title: Some("All about baboons".to_string()),
+///         }),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(9) + Duration::seconds(58),
+///         title: Some("Steve Jobs".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let description =
+///     chapters::to_description_with_links(&chapters).expect("Failed to write chapters");
+/// assert_eq!(
+///     description,
+///     r#"00:00 The Movement - https://example.com/the-movement
+/// 05:04 Baboons - All about baboons
+/// 09:58 Steve Jobs
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_description_with_links(chapters: &[Chapter]) -> Result<String, String> {
+    write_description(chapters, &ToDescriptionOptions::default(), true)
+}
+
+fn write_description(
+    chapters: &[Chapter],
+    options: &ToDescriptionOptions,
+    with_links: bool,
+) -> Result<String, String> {
+    // Skipped chapters are left out before formatting, so that they don't affect whether hours are
+    // included in the timestamps.
+    let titled: Vec<Chapter>;
+    let chapters = if options.missing_title == MissingTitlePolicy::Skip {
+        titled = chapters
+            .iter()
+            .filter(|chapter| chapter.title.is_some())
+            .cloned()
+            .collect();
+        &titled
+    } else {
+        chapters
+    };
+
+    let mut description = String::new();
+
+    for (i, (chapter, timestamp)) in chapters
+        .iter()
+        .zip(description_timestamps(chapters, options))
+        .enumerate()
+    {
+        let title = match (&chapter.title, &options.missing_title) {
+            (Some(title), _) => title,
+            (None, MissingTitlePolicy::Placeholder(placeholder)) => placeholder,
+            (None, _) => return Err(format!("Chapter {} has no title", i)),
+        };
+        let line = format!("{} {}", timestamp, title);
+        description.push_str(&line);
+        if let Some(link) = chapter.link.as_ref().filter(|_| with_links) {
+            description.push_str(" - ");
+            match &link.title {
+                Some(link_title) => description.push_str(link_title),
+                None => description.push_str(link.url.as_str()),
+            }
+        }
+        description.push('\n');
+    }
+
+    Ok(description)
+}
+
+/// Formats the start times of chapters for a description. Hours are included in all timestamps if
+/// any chapter starts at or after an hour, unless the options say otherwise.
+fn description_timestamps(chapters: &[Chapter], options: &ToDescriptionOptions) -> Vec<String> {
+    let at_least_an_hour =
+        |chapter: &Chapter| options.rounding.apply(chapter.start) >= Duration::hours(1);
+    let any_at_least_an_hour = chapters.iter().any(at_least_an_hour);
+
+    chapters
+        .iter()
+        .map(|chapter| {
+            let include_hours = match options.hour_formatting {
+                HourFormatting::Uniform => any_at_least_an_hour,
+                HourFormatting::PerChapter => at_least_an_hour(chapter),
+            };
+            let timestamp_type = if include_hours {
+                TimestampType::HhMmSs
+            } else {
+                TimestampType::MmSs
+            };
+            duration_to_timestamp(
+                chapter.start,
+                timestamp_type,
+                options.rounding,
+                options.padding,
+            )
+        })
+        .collect()
+}
+
+/// Writes [chapters](crate::Chapter) to a [Markdown](https://commonmark.org) bulleted list.
+///
+/// Each chapter becomes a line like `- 05:04 Title`; if the chapter has a
+/// [link](crate::Chapter::link), the timestamp links to it, e.g., `- [05:04](https://example.com)
+/// Title`. [Hidden](crate::Chapter::hidden) chapters are skipped. Timestamps are formatted like in
+/// [to_description].
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("The Movement".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5) + Duration::seconds(4),
+///         title: Some("Baboons".to_string()),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com/baboons").unwrap(),
+///             title: None,
+///         }),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(7),
+///         title: Some("Sponsor".to_string()),
+///         hidden: true,
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(9) + Duration::seconds(58),
+///         title: Some("Steve Jobs".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let markdown = chapters::to_markdown(&chapters).expect("Failed to write chapters");
+/// assert_eq!(
+///     markdown,
+///     r#"- 00:00 The Movement
+/// - [05:04](https://example.com/baboons) Baboons
+/// - 09:58 Steve Jobs
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_markdown(chapters: &[Chapter]) -> Result<String, String> {
+    let (indices, visible): (Vec<usize>, Vec<Chapter>) = chapters
+        .iter()
+        .enumerate()
+        .filter(|(_, chapter)| !chapter.hidden)
+        .map(|(i, chapter)| (i, chapter.clone()))
+        .unzip();
+    let timestamps = description_timestamps(&visible, &ToDescriptionOptions::default());
+
+    let mut markdown = String::new();
+    for ((i, chapter), timestamp) in indices.into_iter().zip(&visible).zip(timestamps) {
+        let title = chapter
+            .title
+            .as_ref()
+            .ok_or_else(|| format!("Chapter {} has no title", i))?;
+        let line = match &chapter.link {
+            Some(link) => format!("- [{}]({}) {}\n", timestamp, link.url, title),
+            None => format!("- {} {}\n", timestamp, title),
+        };
+        markdown.push_str(&line);
+    }
+
+    Ok(markdown)
+}
+
+/// Parses a single timestamp the same way as in [episode descriptions](crate::from_description).
+///
+/// Both `MM:SS` and `HH:MM:SS` are accepted, optionally with up to three digits of fractional
+/// seconds, e.g., "05:04.5". Surrounding whitespace and a leading `+` are ignored.
+///
+/// # Example:
+/// ```rust
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// assert_eq!(chapters::parse_timestamp("05:04"), Ok(Duration::minutes(5) + Duration::seconds(4)));
+/// assert_eq!(
+///     chapters::parse_timestamp(" +1:02:03.5 "),
+///     Ok(Duration::hours(1)
+///         + Duration::minutes(2)
+///         + Duration::seconds(3)
+///         + Duration::milliseconds(500))
+/// );
+/// assert_eq!(
+///     chapters::parse_timestamp("05:60"),
+///     Err("Invalid timestamp `05:60`".to_string())
+/// );
+/// # }
+/// ```
+pub fn parse_timestamp(s: &str) -> Result<Duration, String> {
+    static TIMESTAMPS: OnceLock<[regex::Regex; 2]> = OnceLock::new();
+    let timestamps = TIMESTAMPS.get_or_init(|| {
+        let options = DescriptionOptions::default();
+        [TimestampType::HhMmSs, TimestampType::MmSs].map(|timestamp_type| {
+            regex::Regex::new(&format!("^{}$", timestamp_type.regex_pattern(&options))).unwrap()
+        })
+    });
+
+    let timestamp = s.trim();
+    let timestamp = timestamp.strip_prefix('+').unwrap_or(timestamp);
+    let captures = timestamps
+        .iter()
+        .find_map(|re| re.captures(timestamp))
+        .ok_or_else(|| format!("Invalid timestamp `{}`", s))?;
+    timestamp_from_captures(&captures)
+}
+
+/// Formats a timestamp the same way as in [episode descriptions](crate::to_description), i.e.,
+/// as zero-padded `MM:SS` or, if `with_hours` is set, `HH:MM:SS`, with fractional seconds
+/// truncated.
+///
+/// Without hours, times of an hour or more are written with more than 59 minutes, e.g., "75:30".
+///
+/// # Example:
+/// ```rust
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let time = Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800);
+///
+/// assert_eq!(chapters::format_timestamp(time, false), "05:04");
+/// assert_eq!(chapters::format_timestamp(time, true), "00:05:04");
+/// assert_eq!(chapters::format_timestamp(Duration::minutes(75), false), "75:00");
+/// # }
+/// ```
+pub fn format_timestamp(d: Duration, with_hours: bool) -> String {
+    let timestamp_type = if with_hours {
+        TimestampType::HhMmSs
+    } else {
+        TimestampType::MmSs
+    };
+    duration_to_timestamp(d, timestamp_type, Rounding::default(), Padding::default())
+}
+
+fn timestamp_from_captures(captures: &regex::Captures) -> Result<Duration, String> {
+    let parse_i64 = |capture: Option<regex::Match>| -> Result<i64, String> {
+        capture
+            .map(|m| m.as_str().parse::<i64>().map_err(|e| e.to_string()))
+            .unwrap_or(Ok(0))
+    };
+
+    let hours = parse_i64(captures.name("hours"))?;
+    let minutes = parse_i64(captures.name("minutes"))?;
+    let seconds = parse_i64(captures.name("seconds"))?;
+    // Fractional part has up to three digits, e.g., ".5" is 500 milliseconds.
+    let milliseconds = captures
+        .name("fraction")
+        .map(|m| format!("{:0<3}", m.as_str()).parse::<i64>())
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or(0);
+
+    // Long minutes can be arbitrarily large, so overflow is an error rather than a panic.
+    hours
+        .checked_mul(60)
+        .and_then(|total| total.checked_add(minutes))
+        .and_then(|total| total.checked_mul(60))
+        .and_then(|total| total.checked_add(seconds))
+        .and_then(|total| total.checked_mul(1000))
+        .and_then(|total| total.checked_add(milliseconds))
+        .map(Duration::milliseconds)
+        .ok_or_else(|| String::from("Timestamp is out of range"))
+}
+
+fn duration_to_timestamp(
+    duration: Duration,
+    timestamp_type: TimestampType,
+    rounding: Rounding,
+    padding: Padding,
+) -> String {
+    let duration = rounding.apply(duration);
+    let seconds = duration.num_seconds() % 60;
+    // Without hours, minutes are not wrapped, e.g., "75:30".
+    let total_minutes = duration.num_minutes();
+    let hours = duration.num_hours();
+    let minutes = total_minutes % 60;
+
+    // Width of the first field.
+    let width = match padding {
+        Padding::ZeroPadded => 2,
+        Padding::Unpadded => 1,
+    };
+
+    match timestamp_type {
+        TimestampType::MmSs => format!("{total_minutes:0width$}:{seconds:02}"),
+        TimestampType::HhMmSs => format!("{hours:0width$}:{minutes:02}:{seconds:02}"),
+        TimestampType::MmSsParentheses => format!("({total_minutes:0width$}:{seconds:02})"),
+        TimestampType::HhMmSsParentheses => {
+            format!("({hours:0width$}:{minutes:02}:{seconds:02})")
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{from_json, Chapter};
+
+/// Content types accepted for a JSON chapters file.
+const JSON_CHAPTERS_CONTENT_TYPES: [&str; 2] = ["application/json+chapters", "application/json"];
+
+/// Downloads and reads [chapters](crate::Chapter) from the URL of a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md),
+/// e.g., the `url` attribute of a feed's `<podcast:chapters>` element.
+///
+/// The response must have status 200 and content type `application/json+chapters` (or plain
+/// `application/json`, which many servers use instead); its body is then read with
+/// [from_json](crate::from_json).
+///
+/// # Example:
+/// ```rust,no_run
+/// # fn main() {
+/// let url = url::Url::parse("https://example.com/episode-1/chapters.json").unwrap();
+/// let chapters = chapters::from_chapters_url(&url).expect("Failed to fetch chapters");
+/// # }
+/// ```
+pub fn from_chapters_url(url: &url::Url) -> Result<Vec<Chapter>, String> {
+    let response = match ureq::get(url.as_str()).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status, _)) => {
+            return Err(format!("Unexpected HTTP status {} from `{}`", status, url))
+        }
+        Err(e) => return Err(format!("Error fetching `{}`: {}", url, e)),
+    };
+    // Other successful statuses, such as 204, have no chapters to read.
+    if response.status() != 200 {
+        return Err(format!(
+            "Unexpected HTTP status {} from `{}`",
+            response.status(),
+            url
+        ));
+    }
+
+    let content_type = response.content_type().to_lowercase();
+    if !JSON_CHAPTERS_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(format!(
+            "Unexpected content type `{}` from `{}`",
+            content_type, url
+        ));
+    }
+
+    from_json(response.into_reader())
+}
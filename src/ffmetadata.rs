@@ -0,0 +1,262 @@
+use crate::Chapter;
+use chrono::Duration;
+
+/// Characters that have to be escaped with a backslash in FFmetadata values.
+const SPECIAL_CHARACTERS: [char; 5] = ['=', ';', '#', '\\', '\n'];
+
+/// Reads [chapters](crate::Chapter) from an [FFmetadata file](https://ffmpeg.org/ffmpeg-formats.html#Metadata-1),
+/// e.g., as written by `ffmpeg -i episode.mp3 -f ffmetadata metadata.txt`.
+///
+/// Every `[CHAPTER]` section becomes a chapter. Its `START` and `END` are given in units of its
+/// `TIMEBASE` (1/1000 of a second if missing), and its `title` becomes the chapter title. A
+/// chapter whose end is equal to its start has no [end](crate::Chapter::end). Global metadata,
+/// stream sections, and comments are ignored.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let ffmetadata = r#";FFMETADATA1
+/// title=Episode 42
+///
+/// [CHAPTER]
+/// TIMEBASE=1/1000
+/// START=0
+/// END=30500
+/// title=Intro
+///
+/// [CHAPTER]
+/// TIMEBASE=1/10
+/// START=305
+/// END=600
+/// title=Baboons \= apes
+/// "#;
+///
+/// let chapters = chapters::from_ffmetadata(ffmetadata.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::milliseconds(30_500)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::milliseconds(30_500),
+///             end: Some(Duration::minutes(1)),
+///             title: Some("Baboons = apes".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_ffmetadata<R: std::io::Read>(mut reader: R) -> Result<Vec<Chapter>, String> {
+    let mut ffmetadata = String::new();
+    reader
+        .read_to_string(&mut ffmetadata)
+        .map_err(|e| format!("Error reading FFmetadata file: {}", e))?;
+
+    let mut lines = ffmetadata.trim_start_matches('\u{feff}').lines();
+    if lines.next().map(|line| line.trim_end()) != Some(";FFMETADATA1") {
+        return Err(String::from(
+            "FFmetadata file does not start with `;FFMETADATA1`",
+        ));
+    }
+
+    // Values of the current `[CHAPTER]` section, if any.
+    let mut sections: Vec<Option<Vec<(String, String)>>> = Vec::new();
+    let mut entry = String::new();
+    for line in lines {
+        // A backslash at the end of a line escapes the line break.
+        entry.push_str(line);
+        let trailing_backslashes = line.chars().rev().take_while(|&c| c == '\\').count();
+        if trailing_backslashes % 2 == 1 {
+            entry.pop();
+            entry.push('\n');
+            continue;
+        }
+        let line = std::mem::take(&mut entry);
+
+        if line.starts_with(';') || line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            sections.push((line.trim() == "[CHAPTER]").then(Vec::new));
+            continue;
+        }
+        if let Some(Some(values)) = sections.last_mut() {
+            if let Some((key, value)) = split_entry(&line) {
+                values.push((key, value));
+            }
+        }
+    }
+
+    sections
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, values)| {
+            let value = |key: &str| {
+                values
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, value)| value.as_str())
+            };
+
+            let timebase = value("TIMEBASE").unwrap_or("1/1000");
+            let start = value("START")
+                .ok_or_else(|| format!("Chapter {} has no START", i))
+                .and_then(|start| parse_time(start, timebase))?;
+            let end = value("END")
+                .map(|end| parse_time(end, timebase))
+                .transpose()?;
+
+            Ok(Chapter {
+                start,
+                end: end.filter(|&end| end != start),
+                title: value("title")
+                    .filter(|title| !title.is_empty())
+                    .map(String::from),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Writes [chapters](crate::Chapter) to an [FFmetadata file](https://ffmpeg.org/ffmpeg-formats.html#Metadata-1),
+/// which can be passed to `ffmpeg -i episode.mp3 -i metadata.txt -map_metadata 1 -codec copy
+/// output.mp3` to add the chapters to a media file.
+///
+/// Each chapter is written as a `[CHAPTER]` section with times in milliseconds. FFmpeg requires
+/// every chapter to have an end, so a chapter without an [end](crate::Chapter::end) ends where the
+/// next one starts; the last such chapter ends at its own start. Special characters in titles are
+/// escaped with a backslash.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter::at_seconds(0.0, "Intro"),
+///     Chapter {
+///         end: Some(Duration::minutes(1)),
+///         ..Chapter::at_seconds(30.5, "Baboons = apes")
+///     },
+/// ];
+///
+/// let ffmetadata = chapters::to_ffmetadata(&chapters).expect("Failed to write chapters");
+///
+/// assert_eq!(
+///     ffmetadata,
+///     r#";FFMETADATA1
+///
+/// [CHAPTER]
+/// TIMEBASE=1/1000
+/// START=0
+/// END=30500
+/// title=Intro
+///
+/// [CHAPTER]
+/// TIMEBASE=1/1000
+/// START=30500
+/// END=60000
+/// title=Baboons \= apes
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_ffmetadata(chapters: &[Chapter]) -> Result<String, String> {
+    let mut ffmetadata = String::from(";FFMETADATA1\n");
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        let end = chapter
+            .implied_end(chapters.get(i + 1), None)
+            .unwrap_or(chapter.start);
+        if chapter.start < Duration::zero() || end < chapter.start {
+            return Err(format!("Chapter {} has invalid times", i));
+        }
+
+        ffmetadata.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\n",
+            chapter.start.num_milliseconds(),
+            end.num_milliseconds()
+        ));
+        if let Some(title) = &chapter.title {
+            ffmetadata.push_str(&format!("title={}\n", escape(title)));
+        }
+    }
+
+    Ok(ffmetadata)
+}
+
+/// Splits a `key=value` entry at the first unescaped `=`, unescaping both parts.
+fn split_entry(entry: &str) -> Option<(String, String)> {
+    let mut key = String::new();
+    let mut chars = entry.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => key.extend(chars.next()),
+            '=' => return Some((key, unescape(chars.as_str()))),
+            c => key.push(c),
+        }
+    }
+    None
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if SPECIAL_CHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Converts a time in units of `timebase`, e.g., "1/1000", to a duration, rounding to the nearest
+/// millisecond.
+fn parse_time(time: &str, timebase: &str) -> Result<Duration, String> {
+    let invalid_timebase = || format!("Invalid TIMEBASE `{}`", timebase);
+    let (numerator, denominator) = timebase.split_once('/').ok_or_else(invalid_timebase)?;
+    let numerator: i128 = numerator.trim().parse().map_err(|_| invalid_timebase())?;
+    let denominator: i128 = denominator.trim().parse().map_err(|_| invalid_timebase())?;
+    if numerator <= 0 || denominator <= 0 {
+        return Err(invalid_timebase());
+    }
+
+    let invalid_time = || format!("Invalid time `{}`", time);
+    let time: i128 = time.trim().parse().map_err(|_| invalid_time())?;
+    if time < 0 {
+        return Err(invalid_time());
+    }
+
+    let milliseconds = numerator
+        .checked_mul(1000)
+        .and_then(|numerator| time.checked_mul(numerator))
+        .map(|product| (product + denominator / 2) / denominator)
+        .and_then(|milliseconds| i64::try_from(milliseconds).ok())
+        .ok_or_else(invalid_time)?;
+    Ok(Duration::milliseconds(milliseconds))
+}
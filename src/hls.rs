@@ -0,0 +1,241 @@
+use crate::Chapter;
+use chrono::{DateTime, Duration, FixedOffset, SecondsFormat, Utc};
+use std::collections::HashMap;
+
+/// Reads [chapters](crate::Chapter) from the `EXT-X-DATERANGE` tags of an [HLS
+/// playlist](https://datatracker.ietf.org/doc/html/rfc8216).
+///
+/// Date ranges without a `CLASS` attribute or with a `CLASS` containing "chapter" (e.g.,
+/// `com.apple.hls.chapters`) are treated as chapters; other date ranges, such as ad
+/// interstitials, are ignored. Chapter start times are relative to the first
+/// `EXT-X-PROGRAM-DATE-TIME` tag, or to the earliest chapter if the playlist has no such tag.
+/// The end time is taken from `END-DATE` or `DURATION`, and the title from the `X-TITLE`
+/// attribute.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let playlist = r#"#EXTM3U
+/// #EXT-X-VERSION:6
+/// #EXT-X-PROGRAM-DATE-TIME:2023-10-01T12:00:00.000Z
+/// #EXT-X-DATERANGE:ID="chp-1",CLASS="com.apple.hls.chapters",START-DATE="2023-10-01T12:00:00.000Z",DURATION=30.5,X-TITLE="Intro"
+/// #EXT-X-DATERANGE:ID="ad-1",CLASS="com.apple.hls.interstitial",START-DATE="2023-10-01T12:00:10.000Z",DURATION=5
+/// #EXT-X-DATERANGE:ID="chp-2",CLASS="com.apple.hls.chapters",START-DATE="2023-10-01T12:00:30.500Z",X-TITLE="Baboons, apes, and more"
+/// #EXTINF:10.0,
+/// segment0.ts
+/// "#;
+///
+/// let chapters = chapters::from_hls_playlist(playlist).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::seconds(30) + Duration::milliseconds(500)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(30) + Duration::milliseconds(500),
+///             title: Some("Baboons, apes, and more".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_hls_playlist(playlist: &str) -> Result<Vec<Chapter>, String> {
+    let mut program_date_time = None;
+    let mut date_ranges = Vec::new();
+
+    for line in playlist.lines().map(|line| line.trim()) {
+        if let Some(value) = line.strip_prefix("#EXT-X-PROGRAM-DATE-TIME:") {
+            if program_date_time.is_none() {
+                program_date_time = Some(parse_date(value)?);
+            }
+        } else if let Some(attributes) = line.strip_prefix("#EXT-X-DATERANGE:") {
+            let attributes = parse_attributes(attributes)?;
+            let is_chapter = attributes
+                .get("CLASS")
+                .is_none_or(|class| class.to_lowercase().contains("chapter"));
+            if is_chapter {
+                date_ranges.push(attributes);
+            }
+        }
+    }
+
+    let mut ranges = Vec::new();
+    for attributes in date_ranges {
+        let start_date = parse_date(
+            attributes
+                .get("START-DATE")
+                .ok_or("Date range is missing `START-DATE`")?,
+        )?;
+        let end_date = match (attributes.get("END-DATE"), attributes.get("DURATION")) {
+            (Some(end_date), _) => Some(parse_date(end_date)?),
+            (None, Some(duration)) => Some(
+                start_date
+                    .checked_add_signed(parse_seconds(duration)?)
+                    .ok_or_else(|| {
+                        format!("Date range with duration `{}` is out of range", duration)
+                    })?,
+            ),
+            (None, None) => None,
+        };
+        ranges.push((start_date, end_date, attributes.get("X-TITLE").cloned()));
+    }
+
+    let anchor = match program_date_time.or(ranges.iter().map(|range| range.0).min()) {
+        Some(anchor) => anchor,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut chapters: Vec<Chapter> = ranges
+        .into_iter()
+        .map(|(start_date, end_date, title)| Chapter {
+            start: start_date - anchor,
+            end: end_date.map(|end_date| end_date - anchor),
+            title,
+            ..Default::default()
+        })
+        .collect();
+
+    chapters.sort();
+
+    Ok(chapters)
+}
+
+/// Writes [chapters](crate::Chapter) as `EXT-X-DATERANGE` tags to be added to an [HLS
+/// playlist](https://datatracker.ietf.org/doc/html/rfc8216).
+///
+/// Every chapter becomes a line like `#EXT-X-DATERANGE:ID="chp-1",START-DATE="...",DURATION=30.5,X-TITLE="Intro"`,
+/// where the start date is `start_date` (usually the playlist's `EXT-X-PROGRAM-DATE-TIME`) plus
+/// the chapter's start. The duration is taken from the chapter's end or, failing that, the start
+/// of the next chapter, and is left out for a last chapter without an end. HLS strings cannot
+/// contain double quotes or line breaks, so these are replaced in titles with single quotes and
+/// spaces, respectively.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::{Duration, TimeZone, Utc};
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(30) + Duration::milliseconds(500),
+///         title: Some(r#"The "Baboons""#.to_string()),
+///         ..Default::default()
+///     },
+/// ];
+/// let start_date = Utc.with_ymd_and_hms(2023, 10, 1, 12, 0, 0).unwrap();
+///
+/// let dateranges = chapters::to_hls_dateranges(&chapters, start_date)
+///     .expect("Failed to write chapters");
+///
+/// assert_eq!(
+///     dateranges,
+///     r#"#EXT-X-DATERANGE:ID="chp-1",START-DATE="2023-10-01T12:00:00.000Z",DURATION=30.5,X-TITLE="Intro"
+/// #EXT-X-DATERANGE:ID="chp-2",START-DATE="2023-10-01T12:00:30.500Z",X-TITLE="The 'Baboons'"
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_hls_dateranges(
+    chapters: &[Chapter],
+    start_date: DateTime<Utc>,
+) -> Result<String, String> {
+    let mut dateranges = String::new();
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        let date = start_date
+            .checked_add_signed(chapter.start)
+            .ok_or_else(|| format!("Chapter {} starts at an out-of-range date", i))?
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+        dateranges.push_str(&format!(
+            "#EXT-X-DATERANGE:ID=\"chp-{}\",START-DATE=\"{}\"",
+            i + 1,
+            date
+        ));
+
+        if let Some(end) = chapter.implied_end(chapters.get(i + 1), None) {
+            let duration = end
+                .checked_sub(&chapter.start)
+                .ok_or_else(|| format!("Chapter {} has an out-of-range duration", i))?;
+            if duration < Duration::zero() {
+                return Err(format!("Chapter {} ends before it starts", i));
+            }
+            dateranges.push_str(&format!(
+                ",DURATION={}",
+                duration.num_milliseconds() as f64 / 1000.0
+            ));
+        }
+
+        if let Some(title) = &chapter.title {
+            let title = title.replace('"', "'").replace(['\r', '\n'], " ");
+            dateranges.push_str(&format!(",X-TITLE=\"{}\"", title));
+        }
+        dateranges.push('\n');
+    }
+
+    Ok(dateranges)
+}
+
+fn parse_date(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map_err(|e| format!("Invalid date `{}`: {}", value, e))
+}
+
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds = value
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid duration `{}`: {}", value, e))?;
+    // Largest number of seconds whose milliseconds fit into an i64.
+    const MAX_SECONDS: f64 = i64::MAX as f64 / 1000.0;
+    if !(0.0..MAX_SECONDS).contains(&seconds) {
+        return Err(format!(
+            "Invalid duration `{}`, expected a non-negative number of seconds",
+            value
+        ));
+    }
+    Ok(Duration::milliseconds((seconds * 1000.0).round() as i64))
+}
+
+/// Parses an HLS attribute list, e.g., `ID="chp-1",DURATION=30.5`. Quotes around string values
+/// are removed.
+fn parse_attributes(attributes: &str) -> Result<HashMap<String, String>, String> {
+    let mut parsed = HashMap::new();
+    let mut rest = attributes.trim();
+
+    while !rest.is_empty() {
+        let (name, after_name) = rest
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid attribute list `{}`", attributes))?;
+
+        let (value, after_value) = if let Some(quoted) = after_name.strip_prefix('"') {
+            let end = quoted
+                .find('"')
+                .ok_or_else(|| format!("Unterminated quoted string in `{}`", attributes))?;
+            (&quoted[..end], &quoted[end + 1..])
+        } else {
+            let end = after_name.find(',').unwrap_or(after_name.len());
+            (&after_name[..end], &after_name[end..])
+        };
+
+        parsed.insert(name.trim().to_string(), value.to_string());
+        rest = after_value.trim_start_matches(',').trim_start();
+    }
+
+    Ok(parsed)
+}
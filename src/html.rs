@@ -0,0 +1,188 @@
+use crate::description::parse_description;
+use crate::{Chapter, DescriptionOptions, Link};
+use std::sync::OnceLock;
+
+/// Elements that start a new line of text.
+const BLOCK_ELEMENTS: [&str; 12] = [
+    "br",
+    "p",
+    "div",
+    "li",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+];
+
+/// Reads [chapters](crate::Chapter) from HTML [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes).
+///
+/// Tags are stripped and entities such as `&amp;` are decoded, with line breaks, paragraphs, list
+/// items and other block elements starting a new line; the resulting text is then read like in
+/// [from_description](crate::from_description). If a chapter's line contains a link (`<a href="...">`), e.g., around the
+/// timestamp or the title, the first such link becomes the chapter's [link](crate::Chapter::link).
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let html = r#"<p>Chapters:</p>
+/// <p><a href="https://example.com/episode?t=0">00:00</a> Intro<br>
+/// <a href="https://example.com/episode?t=304">05:04</a> Baboons &amp; Steve Jobs</p>"#;
+///
+/// let chapters = chapters::from_description_html(html).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com/episode?t=0").unwrap(),
+///                 title: None,
+///             }),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5) + Duration::seconds(4),
+///             title: Some("Baboons & Steve Jobs".to_string()),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com/episode?t=304").unwrap(),
+///                 title: None,
+///             }),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_description_html(html: &str) -> Result<Vec<Chapter>, String> {
+    let (text, line_links) = html_to_text(html);
+
+    let parsed = parse_description(&text, &DescriptionOptions::default())?;
+    let mut chapters = parsed.chapters;
+    for (chapter, line) in chapters.iter_mut().zip(parsed.chapter_lines) {
+        chapter.link = line_links
+            .get(line)
+            .cloned()
+            .flatten()
+            .map(|url| Link { url, title: None });
+    }
+
+    Ok(chapters)
+}
+
+/// Converts HTML to plain text, returning the text and the first valid absolute link on each of
+/// its lines.
+fn html_to_text(html: &str) -> (String, Vec<Option<url::Url>>) {
+    static HREF: OnceLock<regex::Regex> = OnceLock::new();
+    let href = HREF.get_or_init(|| {
+        regex::Regex::new(r#"(?i)\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap()
+    });
+
+    let mut text = String::new();
+    let mut line_links = vec![None];
+    let mut rest = html;
+
+    while let Some(tag_start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..tag_start]));
+        let tag_end = match rest[tag_start..].find('>') {
+            Some(tag_end) => tag_start + tag_end,
+            // An unclosed `<` is kept as text.
+            None => {
+                text.push_str(&decode_entities(&rest[tag_start..]));
+                rest = "";
+                break;
+            }
+        };
+        let tag = &rest[tag_start + 1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if BLOCK_ELEMENTS.contains(&name.as_str()) {
+            text.push('\n');
+        } else if name == "a" && !tag.starts_with('/') {
+            let url = href
+                .captures(tag)
+                .and_then(|captures| captures.iter().skip(1).flatten().next())
+                .and_then(|m| url::Url::parse(&decode_entities(m.as_str())).ok());
+            let line = text.matches('\n').count();
+            line_links.resize(line + 1, None);
+            if line_links[line].is_none() {
+                line_links[line] = url;
+            }
+        }
+    }
+    text.push_str(&decode_entities(rest));
+    line_links.resize(text.matches('\n').count() + 1, None);
+
+    (text, line_links)
+}
+
+/// Decodes numeric character references and common named HTML entities. Unknown entities are kept
+/// as is.
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "hellip" => Some('…'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            _ => {
+                let code = match entity.strip_prefix('#') {
+                    Some(hex) if hex.starts_with(['x', 'X']) => {
+                        u32::from_str_radix(&hex[1..], 16).ok()
+                    }
+                    Some(decimal) => decimal.parse().ok(),
+                    None => None,
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
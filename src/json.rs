@@ -0,0 +1,875 @@
+#[cfg(feature = "rssblue")]
+use crate::RemoteEntity;
+use crate::{serialization, Chapter, Image, Link, Location};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+use std::sync::OnceLock;
+
+impl From<PodcastNamespaceChapter> for Chapter {
+    fn from(podcast_namespace_chapter: PodcastNamespaceChapter) -> Self {
+        Self {
+            start: podcast_namespace_chapter.start_time,
+            end: podcast_namespace_chapter.end_time,
+            title: podcast_namespace_chapter.title,
+            language: podcast_namespace_chapter.language,
+            image: podcast_namespace_chapter.img.map(|url| Image::Url {
+                url,
+                mime_type: podcast_namespace_chapter.img_type,
+                width: podcast_namespace_chapter.img_width,
+                height: podcast_namespace_chapter.img_height,
+            }),
+            #[cfg(feature = "rssblue")]
+            images: podcast_namespace_chapter
+                .images
+                .into_iter()
+                .map(Image::from)
+                .collect(),
+            #[cfg(not(feature = "rssblue"))]
+            images: Vec::new(),
+            link: podcast_namespace_chapter.url.map(|url| Link {
+                url,
+                #[cfg(feature = "rssblue")]
+                title: podcast_namespace_chapter.link_title,
+                #[cfg(not(feature = "rssblue"))]
+                title: None,
+            }),
+            hidden: !podcast_namespace_chapter.toc.unwrap_or(true),
+            location: podcast_namespace_chapter.location,
+            element_id: None,
+            start_byte: None,
+            end_byte: None,
+            #[cfg(feature = "rssblue")]
+            remote_entity: podcast_namespace_chapter.remote_entity,
+        }
+    }
+}
+
+/// Chapters of the [Podcast namespace](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct PodcastNamespaceChapters {
+    version: String,
+    chapters: Vec<PodcastNamespaceChapter>,
+}
+
+impl From<&[Chapter]> for PodcastNamespaceChapters {
+    fn from(chapters: &[Chapter]) -> Self {
+        Self {
+            version: "1.2.0".to_string(),
+            chapters: chapters.iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+/// Individual chapter inside [Chapters](crate::PodcastNamespaceChapters).
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastNamespaceChapter {
+    /// The starting time of the chapter.
+    #[serde(
+        deserialize_with = "serialization::float_to_duration",
+        serialize_with = "serialization::duration_to_float"
+    )]
+    start_time: Duration,
+    /// The end time of the chapter.
+    #[serde(
+        default,
+        deserialize_with = "serialization::float_to_duration_option",
+        serialize_with = "serialization::duration_option_to_float_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    end_time: Option<Duration>,
+    /// The title of this chapter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// The BCP 47 language tag of the chapter's title and other metadata. Not part of the
+    /// specification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    /// The url of an image to use as chapter art.
+    #[serde(
+        default,
+        deserialize_with = "serialization::string_to_url",
+        serialize_with = "serialization::url_option_to_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    img: Option<url::Url>,
+    /// The MIME type of the image in `img`, e.g., "image/jpeg". Not part of the specification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    img_type: Option<String>,
+    /// The width of the image in `img` in pixels. Not part of the specification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    img_width: Option<u32>,
+    /// The height of the image in `img` in pixels. Not part of the specification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    img_height: Option<u32>,
+    /// The urls of additional images besides `img`. Used internally by RSS Blue.
+    #[cfg(feature = "rssblue")]
+    #[serde(
+        default,
+        deserialize_with = "serialization::strings_to_urls",
+        serialize_with = "serialization::urls_to_strings",
+        skip_serializing_if = "Vec::is_empty",
+        rename = "rssblue:images"
+    )]
+    images: Vec<url::Url>,
+    /// The url of a web page or supporting document that's related to the topic of this chapter.
+    #[serde(
+        default,
+        deserialize_with = "serialization::string_to_url",
+        serialize_with = "serialization::url_option_to_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    url: Option<url::Url>,
+    /// The title of the web page or supporting document in `url`. Used internally by RSS Blue.
+    #[cfg(feature = "rssblue")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "rssblue:linkTitle"
+    )]
+    link_title: Option<String>,
+    /// If this property is present and set to false, this chapter should not display visibly to the user in either the table of contents or as a jump-to point in the user interface.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    toc: Option<bool>,
+    /// This object defines an optional location that is tied to this chapter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+    #[cfg(feature = "rssblue")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "rssblue:remoteEntity"
+    )]
+    remote_entity: Option<RemoteEntity>,
+}
+
+impl<'a> From<&'a Chapter> for PodcastNamespaceChapter {
+    fn from(chapter: &'a Chapter) -> Self {
+        let image_url = match &chapter.image {
+            Some(Image::Url {
+                url,
+                mime_type,
+                width,
+                height,
+            }) => Some((url, mime_type, width, height)),
+            _ => None,
+        };
+
+        Self {
+            start_time: chapter.start,
+            end_time: chapter.end,
+            title: chapter.title.clone(),
+            language: chapter.language.clone(),
+            img: image_url.map(|(url, _, _, _)| url.clone()),
+            img_type: image_url.and_then(|(_, mime_type, _, _)| mime_type.clone()),
+            img_width: image_url.and_then(|(_, _, width, _)| *width),
+            img_height: image_url.and_then(|(_, _, _, height)| *height),
+            // Embedded images cannot be referenced in JSON.
+            #[cfg(feature = "rssblue")]
+            images: chapter
+                .images
+                .iter()
+                .filter_map(|image| match image {
+                    Image::Url { url, .. } => Some(url.clone()),
+                    Image::Data { .. } => None,
+                })
+                .collect(),
+            url: chapter.link.as_ref().map(|link| link.url.clone()),
+            #[cfg(feature = "rssblue")]
+            link_title: chapter.link.as_ref().and_then(|link| link.title.clone()),
+            toc: if chapter.hidden { Some(false) } else { None },
+            location: chapter.location.clone(),
+            #[cfg(feature = "rssblue")]
+            remote_entity: chapter.remote_entity.clone(),
+        }
+    }
+}
+
+/// Reads [chapters](crate::Chapter) from a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
+///
+/// Besides the standard fields, the optional `imgType`, `imgWidth`, and `imgHeight` keys are
+/// read into the [image](crate::Image::Url)'s MIME type and dimensions, and the optional
+/// `language` key into the chapter's [language](crate::Chapter::language). Negative or too large
+/// `startTime` and `endTime` values are rejected with an error naming the value. Times given as
+/// numeric strings, e.g., `"30.5"`, which some tools write, are read as numbers; use
+/// [from_json_strict] to reject them. Any other `endTime` value except `null` is an error.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Image, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let json = r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "endTime": 30.5,
+///       "title": "Chapter 1",
+///       "img": "https://example.com/chapter-1.jpg",
+///       "url": "https://example.com/chapter-1"
+///     },
+///     {
+///       "startTime": 30.5,
+///       "title": "Chapter 2"
+///     },
+///     {
+///       "startTime": 55,
+///       "title": "Hidden chapter",
+///       "toc": false
+///     },
+///     {
+///       "startTime": 60,
+///       "endTime": 90,
+///       "title": "Chapter 3",
+///       "img": "https://example.com/chapter-3.jpg"
+///     }
+///   ]
+/// }"#;
+///
+/// let chapters = chapters::from_json(json.as_bytes()).unwrap();
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::seconds(0),
+///             end: Some(Duration::seconds(30) + Duration::milliseconds(500)),
+///             title: Some("Chapter 1".to_string()),
+///             image: Some(Image::from(
+///                 url::Url::parse("https://example.com/chapter-1.jpg").unwrap()
+///             )),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com/chapter-1").unwrap(),
+///                 title: None,
+///             }),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(30) + Duration::milliseconds(500),
+///             end: None,
+///             title: Some("Chapter 2".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(55),
+///             end: None,
+///             title: Some("Hidden chapter".to_string()),
+///             hidden: true,
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(60),
+///             end: Some(Duration::seconds(90)),
+///             title: Some("Chapter 3".to_string()),
+///             image: Some(Image::from(
+///                 url::Url::parse("https://example.com/chapter-3.jpg").unwrap()
+///             )),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_json<R: std::io::Read>(reader: R) -> Result<Vec<Chapter>, String> {
+    let podcast_namespace_chapters: PodcastNamespaceChapters =
+        serde_json::from_slice(&read_without_bom(reader)?).map_err(|e| e.to_string())?;
+    Ok(podcast_namespace_chapters
+        .chapters
+        .into_iter()
+        .map(|c| c.into())
+        .collect())
+}
+
+/// Reads [chapters](crate::Chapter) from a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md),
+/// failing on invalid URLs and quoted times.
+///
+/// [from_json] silently ignores an `img` or `url` that is not a valid URL, whereas this function
+/// returns an error identifying the chapter, the field and the bad value. Empty strings are still
+/// read as no URL. Likewise, a `startTime` or `endTime` given as a string, e.g., `"30.5"`, is an
+/// error rather than being read as a number.
+///
+/// # Example:
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let json = r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1",
+///       "img": "artwork/chapter1.png"
+///     }
+///   ]
+/// }"#;
+///
+/// assert_eq!(
+///     chapters::from_json_strict(json.as_bytes()),
+///     Err("Invalid URL `artwork/chapter1.png` in `img` of chapter 0: relative URL without a base".to_string())
+/// );
+///
+/// let chapters = chapters::from_json(json.as_bytes()).expect("Failed to parse chapters");
+/// assert_eq!(chapters[0].image, None);
+/// # }
+/// ```
+pub fn from_json_strict<R: std::io::Read>(reader: R) -> Result<Vec<Chapter>, String> {
+    let json: serde_json::Value =
+        serde_json::from_slice(&read_without_bom(reader)?).map_err(|e| e.to_string())?;
+
+    let json_chapters = json
+        .get("chapters")
+        .and_then(|chapters| chapters.as_array());
+    for (i, chapter) in json_chapters.into_iter().flatten().enumerate() {
+        for field in ["startTime", "endTime"] {
+            if let Some(value) = chapter.get(field).and_then(|value| value.as_str()) {
+                return Err(format!(
+                    "Time `{}` in `{}` of chapter {} is a string, not a number",
+                    value, field, i
+                ));
+            }
+        }
+        for field in ["img", "url"] {
+            if let Some(value) = chapter.get(field).and_then(|value| value.as_str()) {
+                if !value.is_empty() {
+                    url::Url::parse(value).map_err(|e| {
+                        format!(
+                            "Invalid URL `{}` in `{}` of chapter {}: {}",
+                            value, field, i, e
+                        )
+                    })?;
+                }
+            }
+        }
+    }
+
+    chapters_from_json_value(json)
+}
+
+/// Reads [chapters](crate::Chapter) from a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md),
+/// resolving relative URLs against `base`.
+///
+/// Relative `img` and `url` values, e.g., `images/chapter1.jpg`, are resolved like links in a
+/// web page, typically with the feed's URL as the base. Absolute URLs are kept as they are.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Image, Link};
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let json = r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1",
+///       "img": "images/chapter1.jpg",
+///       "url": "https://example.org/"
+///     }
+///   ]
+/// }"#;
+///
+/// let base = url::Url::parse("https://example.com/podcast/feed.xml").unwrap();
+/// let chapters = chapters::from_json_with_base(json.as_bytes(), &base)
+///     .expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters[0].image,
+///     Some(Image::from(
+///         url::Url::parse("https://example.com/podcast/images/chapter1.jpg").unwrap()
+///     ))
+/// );
+/// assert_eq!(
+///     chapters[0].link,
+///     Some(Link {
+///         url: "https://example.org/".parse().unwrap(),
+///         title: None,
+///     })
+/// );
+/// # }
+/// ```
+pub fn from_json_with_base<R: std::io::Read>(
+    reader: R,
+    base: &url::Url,
+) -> Result<Vec<Chapter>, String> {
+    let mut json: serde_json::Value =
+        serde_json::from_slice(&read_without_bom(reader)?).map_err(|e| e.to_string())?;
+
+    let json_chapters = json
+        .get_mut("chapters")
+        .and_then(|chapters| chapters.as_array_mut());
+    for chapter in json_chapters.into_iter().flatten() {
+        for field in ["img", "url"] {
+            if let Some(value) = chapter.get_mut(field) {
+                // An empty string would resolve to the base itself.
+                let url = value
+                    .as_str()
+                    .filter(|value| !value.is_empty())
+                    .and_then(|value| base.join(value).ok());
+                if let Some(url) = url {
+                    *value = url.as_str().into();
+                }
+            }
+        }
+    }
+
+    chapters_from_json_value(json)
+}
+
+/// Reads all of `reader`, dropping the UTF-8 byte order mark that some Windows tools write at the
+/// start of files.
+pub(crate) fn read_without_bom<R: std::io::Read>(mut reader: R) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Error reading JSON: {}", e))?;
+    if bytes.starts_with(b"\xEF\xBB\xBF") {
+        bytes.drain(..3);
+    }
+    Ok(bytes)
+}
+
+/// Converts an already parsed JSON chapters file.
+fn chapters_from_json_value(json: serde_json::Value) -> Result<Vec<Chapter>, String> {
+    let podcast_namespace_chapters: PodcastNamespaceChapters =
+        serde_json::from_value(json).map_err(|e| e.to_string())?;
+    Ok(podcast_namespace_chapters
+        .chapters
+        .into_iter()
+        .map(|c| c.into())
+        .collect())
+}
+
+/// Writes [chapters](crate::Chapter) to a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
+///
+/// An [image](crate::Image::Url)'s MIME type and dimensions, if known, are written to the
+/// optional `imgType`, `imgWidth`, and `imgHeight` keys; embedded images are left out.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Image, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///    Chapter {
+///        start: Duration::zero(),
+///        title: Some("Chapter 1".to_string()),
+///        ..Default::default()
+///    },
+///    Chapter {
+///        start: Duration::seconds(45) + Duration::milliseconds(900),
+///        title: Some("Chapter 2".to_string()),
+///        link: Some(Link {
+///            url: "https://example.com".parse().unwrap(),
+///            title: None,
+///        }),
+///        ..Default::default()
+///    },
+///    Chapter {
+///        start: Duration::minutes(1)+Duration::seconds(5),
+///        title: Some("Hidden chapter".to_string()),
+///        hidden: true,
+///        ..Default::default()
+///    },
+///    Chapter {
+///        start: Duration::minutes(2)+Duration::seconds(10)+Duration::milliseconds(500),
+///        title: Some("Chapter 3".to_string()),
+///        image: Some(Image::from(url::Url::parse("https://example.com/image.png").unwrap())),
+///        ..Default::default()
+///    },
+/// ];
+///
+/// let json_chapters = chapters::to_json(&chapters).expect("Failed to serialize chapters");
+///
+/// assert_eq!(json_chapters, r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1"
+///     },
+///     {
+///       "startTime": 45.9,
+///       "title": "Chapter 2",
+///       "url": "https://example.com/"
+///     },
+///     {
+///       "startTime": 65,
+///       "title": "Hidden chapter",
+///       "toc": false
+///     },
+///     {
+///       "startTime": 130.5,
+///       "title": "Chapter 3",
+///       "img": "https://example.com/image.png"
+///     }
+///   ]
+/// }"#);
+/// # }
+/// ```
+pub fn to_json(chapters: &[Chapter]) -> Result<String, String> {
+    to_json_with_options(chapters, &JsonOptions::default())
+}
+
+/// Converts [chapters](crate::Chapter) to a compact [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md)
+/// without any whitespace, e.g., for embedding in an API response.
+///
+/// The fields are the same as in [to_json], which pretty-prints the JSON instead.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter::at_seconds(0.0, "Intro"),
+///     Chapter {
+///         hidden: true,
+///         ..Chapter::at_seconds(45.9, "Ad")
+///     },
+/// ];
+///
+/// assert_eq!(
+///     chapters::to_json_compact(&chapters).unwrap(),
+///     r#"{"version":"1.2.0","chapters":[{"startTime":0,"title":"Intro"},{"startTime":45.9,"title":"Ad","toc":false}]}"#
+/// );
+/// # }
+/// ```
+pub fn to_json_compact(chapters: &[Chapter]) -> Result<String, String> {
+    let podcast_namespace_chapters: PodcastNamespaceChapters = chapters.into();
+    serde_json::to_string(&podcast_namespace_chapters).map_err(|e| e.to_string())
+}
+
+/// Writes [chapters](crate::Chapter) as a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md) to `writer`.
+///
+/// The output is the same as that of [to_json], but it is written directly, e.g., to a file or a
+/// socket, without building the whole document in memory first. Pass a buffered writer, such as
+/// [std::io::BufWriter], if `writer` is unbuffered.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![Chapter::at_seconds(0.0, "Intro")];
+///
+/// let mut json = Vec::new();
+/// chapters::write_json(&mut json, &chapters).expect("Failed to write chapters");
+///
+/// assert_eq!(String::from_utf8(json).unwrap(), chapters::to_json(&chapters).unwrap());
+/// # }
+/// ```
+pub fn write_json<W: std::io::Write>(writer: W, chapters: &[Chapter]) -> Result<(), String> {
+    let podcast_namespace_chapters: PodcastNamespaceChapters = chapters.into();
+    write_serialized_json(writer, &podcast_namespace_chapters, &JsonOptions::default())
+}
+
+/// Options for [to_json_with_options].
+#[derive(Debug, Clone, Default)]
+pub struct JsonOptions {
+    /// End the output with a newline, as required by tools enforcing POSIX text files. Disabled
+    /// by default.
+    pub trailing_newline: bool,
+    /// Write start and end times with this many decimals, e.g., `30.500` for 3 decimals, as
+    /// required by some validators. By default, times are written as compactly as possible, e.g.,
+    /// `30` or `30.5`.
+    pub decimals: Option<u8>,
+    /// Always write the `toc` field, i.e., `true` for visible chapters and `false` for
+    /// [hidden](crate::Chapter::hidden) ones. By default, `toc` is only written for hidden chapters,
+    /// since consumers treat a missing `toc` as `true`.
+    pub explicit_toc: bool,
+}
+
+/// Writes [chapters](crate::Chapter) to a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md) using the given options.
+///
+/// [to_json] is equivalent to calling this function with the default options.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, JsonOptions};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![Chapter {
+///     start: Duration::zero(),
+///     title: Some("Chapter 1".to_string()),
+///     ..Default::default()
+/// }];
+///
+/// let options = JsonOptions {
+///     trailing_newline: true,
+///     ..Default::default()
+/// };
+/// let json_chapters = chapters::to_json_with_options(&chapters, &options)
+///     .expect("Failed to serialize chapters");
+///
+/// assert_eq!(json_chapters, r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1"
+///     }
+///   ]
+/// }
+/// "#);
+///
+/// let options = JsonOptions {
+///     decimals: Some(3),
+///     ..Default::default()
+/// };
+/// let json_chapters = chapters::to_json_with_options(&chapters, &options)
+///     .expect("Failed to serialize chapters");
+///
+/// assert_eq!(json_chapters, r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0.000,
+///       "title": "Chapter 1"
+///     }
+///   ]
+/// }"#);
+///
+/// let options = JsonOptions {
+///     explicit_toc: true,
+///     ..Default::default()
+/// };
+/// let json_chapters = chapters::to_json_with_options(&chapters, &options)
+///     .expect("Failed to serialize chapters");
+///
+/// assert_eq!(json_chapters, r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1",
+///       "toc": true
+///     }
+///   ]
+/// }"#);
+/// # }
+/// ```
+pub fn to_json_with_options(chapters: &[Chapter], options: &JsonOptions) -> Result<String, String> {
+    let mut podcast_namespace_chapters: PodcastNamespaceChapters = chapters.into();
+    if options.explicit_toc {
+        for (chapter, podcast_namespace_chapter) in chapters
+            .iter()
+            .zip(podcast_namespace_chapters.chapters.iter_mut())
+        {
+            podcast_namespace_chapter.toc = Some(!chapter.hidden);
+        }
+    }
+    serialize_json(&podcast_namespace_chapters, options)
+}
+
+/// Writes [chapters](crate::Chapter) to a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md) with the given `version`.
+///
+/// [to_json] always writes version "1.2.0", whereas some consumers expect a different version of
+/// the specification. The version must look like a semantic version, i.e., two or three numbers
+/// separated by dots, optionally followed by a pre-release and build metadata (e.g., "1.2",
+/// "1.2.0", or "2.0.0-beta.1").
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![Chapter {
+///     start: Duration::zero(),
+///     title: Some("Chapter 1".to_string()),
+///     ..Default::default()
+/// }];
+///
+/// let json_chapters = chapters::to_json_with_version(&chapters, "1.1.0")
+///     .expect("Failed to serialize chapters");
+///
+/// assert_eq!(json_chapters, r#"{
+///   "version": "1.1.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1"
+///     }
+///   ]
+/// }"#);
+///
+/// assert_eq!(
+///     chapters::to_json_with_version(&chapters, "latest"),
+///     Err("Invalid version `latest`".to_string())
+/// );
+/// # }
+/// ```
+pub fn to_json_with_version(chapters: &[Chapter], version: &str) -> Result<String, String> {
+    static VERSION: OnceLock<regex::Regex> = OnceLock::new();
+    let re = VERSION.get_or_init(|| {
+        regex::Regex::new(r"^\d+\.\d+(?:\.\d+)?(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?$").unwrap()
+    });
+    if !re.is_match(version) {
+        return Err(format!("Invalid version `{}`", version));
+    }
+
+    let mut podcast_namespace_chapters: PodcastNamespaceChapters = chapters.into();
+    podcast_namespace_chapters.version = version.to_string();
+    serialize_json(&podcast_namespace_chapters, &JsonOptions::default())
+}
+
+/// Writes [chapters](crate::Chapter) as [JSON Lines](https://jsonlines.org) (NDJSON), i.e., one
+/// chapter object per line.
+///
+/// Chapters are written with the same fields as in [to_json], but compactly and without the
+/// wrapping `chapters` array or `version`. Every line, including the last one, ends with a
+/// newline.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Chapter 1".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(30) + Duration::milliseconds(500),
+///         title: Some("Chapter 2".to_string()),
+///         hidden: true,
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let ndjson = chapters::to_ndjson(&chapters).expect("Failed to serialize chapters");
+///
+/// assert_eq!(
+///     ndjson,
+///     r#"{"startTime":0,"title":"Chapter 1"}
+/// {"startTime":30.5,"title":"Chapter 2","toc":false}
+/// "#
+/// );
+/// # }
+/// ```
+pub fn to_ndjson(chapters: &[Chapter]) -> Result<String, String> {
+    let mut ndjson = String::new();
+    for chapter in chapters {
+        let podcast_namespace_chapter: PodcastNamespaceChapter = chapter.into();
+        ndjson.push_str(
+            &serde_json::to_string(&podcast_namespace_chapter).map_err(|e| e.to_string())?,
+        );
+        ndjson.push('\n');
+    }
+    Ok(ndjson)
+}
+
+/// Reads [chapters](crate::Chapter) from [JSON Lines](https://jsonlines.org) (NDJSON), e.g., as
+/// written by [to_ndjson].
+///
+/// Every non-blank line must hold a single chapter object with the same fields as in a
+/// [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
+/// Blank lines are skipped.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let ndjson = r#"{"startTime": 0, "title": "Chapter 1"}
+///
+/// {"startTime": 30.5, "title": "Chapter 2"}
+/// "#;
+///
+/// let chapters = chapters::from_ndjson(ndjson.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Chapter 1".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(30) + Duration::milliseconds(500),
+///             title: Some("Chapter 2".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+///
+/// assert_eq!(
+///     chapters::from_ndjson(r#"{"title": "No start"}"#.as_bytes()),
+///     Err("Error on line 1: missing field `startTime` at line 1 column 21".to_string())
+/// );
+/// # }
+/// ```
+pub fn from_ndjson<R: std::io::Read>(reader: R) -> Result<Vec<Chapter>, String> {
+    let mut chapters = Vec::new();
+
+    for (i, line) in std::io::BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading NDJSON: {}", e))?;
+        let line = if i == 0 {
+            line.trim_start_matches('\u{feff}')
+        } else {
+            &line
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let podcast_namespace_chapter: PodcastNamespaceChapter =
+            serde_json::from_str(line).map_err(|e| format!("Error on line {}: {}", i + 1, e))?;
+        chapters.push(podcast_namespace_chapter.into());
+    }
+
+    Ok(chapters)
+}
+
+/// Pretty-prints a JSON chapters file.
+fn serialize_json(
+    podcast_namespace_chapters: &PodcastNamespaceChapters,
+    options: &JsonOptions,
+) -> Result<String, String> {
+    let mut buffer = Vec::new();
+    write_serialized_json(&mut buffer, podcast_namespace_chapters, options)?;
+    // The serializer only writes valid UTF-8.
+    String::from_utf8(buffer).map_err(|e| e.to_string())
+}
+
+/// Pretty-prints a JSON chapters file to `writer`.
+fn write_serialized_json<W: std::io::Write>(
+    mut writer: W,
+    podcast_namespace_chapters: &PodcastNamespaceChapters,
+    options: &JsonOptions,
+) -> Result<(), String> {
+    let error = |e: &dyn std::fmt::Display| format!("Error writing JSON: {}", e);
+
+    match options.decimals {
+        Some(decimals) => {
+            let formatter = serialization::FixedDecimalsFormatter::new(decimals);
+            let mut serializer = serde_json::Serializer::with_formatter(&mut writer, formatter);
+            podcast_namespace_chapters.serialize(&mut serializer)
+        }
+        None => serde_json::to_writer_pretty(&mut writer, podcast_namespace_chapters),
+    }
+    .map_err(|e| error(&e))?;
+    if options.trailing_newline {
+        writer.write_all(b"\n").map_err(|e| error(&e))?;
+    }
+    Ok(())
+}
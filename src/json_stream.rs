@@ -0,0 +1,245 @@
+use crate::{Chapter, PodcastNamespaceChapter};
+use std::io::{BufReader, Bytes, Read};
+use std::iter::Peekable;
+
+/// Reads [chapters](crate::Chapter) from a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md)
+/// one at a time.
+///
+/// Unlike [from_json](crate::from_json), only a single chapter is held in memory at once, which
+/// helps with very large chapter lists. The top-level object is scanned as it is read: `version`
+/// is consumed and checked to be a string, other fields are skipped, and every element of the
+/// `chapters` array is yielded as soon as it has been parsed. The iterator stops after the first
+/// error.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let json = r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1"
+///     },
+///     {
+///       "startTime": 30.5,
+///       "title": "Chapter 2"
+///     }
+///   ]
+/// }"#;
+///
+/// let mut chapters = chapters::iter_json(json.as_bytes());
+///
+/// assert_eq!(
+///     chapters.next(),
+///     Some(Ok(Chapter {
+///         start: Duration::zero(),
+///         title: Some("Chapter 1".to_string()),
+///         ..Default::default()
+///     }))
+/// );
+/// assert_eq!(
+///     chapters.next(),
+///     Some(Ok(Chapter {
+///         start: Duration::seconds(30) + Duration::milliseconds(500),
+///         title: Some("Chapter 2".to_string()),
+///         ..Default::default()
+///     }))
+/// );
+/// assert_eq!(chapters.next(), None);
+/// # }
+/// ```
+pub fn iter_json<R: Read>(reader: R) -> impl Iterator<Item = Result<Chapter, String>> {
+    JsonChapters {
+        bytes: BufReader::new(reader).bytes().peekable(),
+        state: State::Start,
+        has_version: false,
+        has_chapters: false,
+    }
+}
+
+/// Where [JsonChapters] is within the top-level object.
+enum State {
+    Start,
+    Fields { first: bool },
+    Chapters { first: bool },
+    Done,
+}
+
+struct JsonChapters<R: Read> {
+    bytes: Peekable<Bytes<BufReader<R>>>,
+    state: State,
+    has_version: bool,
+    has_chapters: bool,
+}
+
+impl<R: Read> Iterator for JsonChapters<R> {
+    type Item = Result<Chapter, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.step() {
+            Ok(chapter) => chapter.map(Ok),
+            Err(e) => {
+                self.state = State::Done;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<R: Read> JsonChapters<R> {
+    /// Advances until the next chapter or the end of the top-level object.
+    fn step(&mut self) -> Result<Option<Chapter>, String> {
+        loop {
+            match self.state {
+                State::Start => {
+                    // Drop the UTF-8 byte order mark that some Windows tools write.
+                    if self
+                        .bytes
+                        .peek()
+                        .is_some_and(|byte| matches!(byte, Ok(0xEF)))
+                    {
+                        for expected in [0xEF, 0xBB, 0xBF] {
+                            if self.next_byte()? != expected {
+                                return Err(String::from("invalid byte order mark"));
+                            }
+                        }
+                    }
+                    self.expect(b'{')?;
+                    self.state = State::Fields { first: true };
+                }
+                State::Fields { first } => {
+                    if self.peek()? == Some(b'}') {
+                        self.bytes.next();
+                        self.state = State::Done;
+                        if !self.has_version {
+                            return Err(String::from("missing field `version`"));
+                        }
+                        if !self.has_chapters {
+                            return Err(String::from("missing field `chapters`"));
+                        }
+                        return Ok(None);
+                    }
+                    if !first {
+                        self.expect(b',')?;
+                    }
+                    let key: String =
+                        serde_json::from_slice(&self.read_value()?).map_err(|e| e.to_string())?;
+                    self.expect(b':')?;
+
+                    match key.as_str() {
+                        "chapters" => {
+                            self.expect(b'[')?;
+                            self.has_chapters = true;
+                            self.state = State::Chapters { first: true };
+                        }
+                        "version" => {
+                            serde_json::from_slice::<String>(&self.read_value()?)
+                                .map_err(|e| e.to_string())?;
+                            self.has_version = true;
+                            self.state = State::Fields { first: false };
+                        }
+                        _ => {
+                            self.read_value()?;
+                            self.state = State::Fields { first: false };
+                        }
+                    }
+                }
+                State::Chapters { first } => {
+                    if self.peek()? == Some(b']') {
+                        self.bytes.next();
+                        self.state = State::Fields { first: false };
+                        continue;
+                    }
+                    if !first {
+                        self.expect(b',')?;
+                    }
+                    self.state = State::Chapters { first: false };
+
+                    let chapter: PodcastNamespaceChapter =
+                        serde_json::from_slice(&self.read_value()?).map_err(|e| e.to_string())?;
+                    return Ok(Some(chapter.into()));
+                }
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    /// Returns the next byte that is not whitespace without consuming it.
+    fn peek(&mut self) -> Result<Option<u8>, String> {
+        while let Some(byte) = self.bytes.peek() {
+            match byte {
+                Ok(b' ' | b'\t' | b'\n' | b'\r') => {
+                    self.bytes.next();
+                }
+                Ok(byte) => return Ok(Some(*byte)),
+                Err(_) => return Err(self.bytes.next().unwrap().unwrap_err().to_string()),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Skips whitespace and consumes the next byte, failing if it is not `expected`.
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        match self.peek()? {
+            Some(byte) if byte == expected => {
+                self.bytes.next();
+                Ok(())
+            }
+            Some(byte) => Err(format!(
+                "expected `{}`, found `{}`",
+                expected as char, byte as char
+            )),
+            None => Err(format!(
+                "expected `{}`, found end of input",
+                expected as char
+            )),
+        }
+    }
+
+    /// Consumes the next byte, failing at the end of input.
+    fn next_byte(&mut self) -> Result<u8, String> {
+        match self.bytes.next() {
+            Some(byte) => byte.map_err(|e| e.to_string()),
+            None => Err(String::from("unexpected end of input")),
+        }
+    }
+
+    /// Reads the raw bytes of the next JSON value.
+    fn read_value(&mut self) -> Result<Vec<u8>, String> {
+        let mut value = Vec::new();
+        let mut depth = 0;
+        let mut in_string = false;
+
+        let first = self.peek()?;
+        if !matches!(first, Some(b'"' | b'{' | b'[')) {
+            // Numbers and literals end at the next delimiter.
+            while let Some(byte) = self.bytes.peek() {
+                match byte {
+                    Ok(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r') => break,
+                    _ => value.push(self.next_byte()?),
+                }
+            }
+            return Ok(value);
+        }
+
+        loop {
+            let byte = self.next_byte()?;
+            value.push(byte);
+            match byte {
+                b'\\' if in_string => value.push(self.next_byte()?),
+                b'"' => in_string = !in_string,
+                b'{' | b'[' if !in_string => depth += 1,
+                b'}' | b']' if !in_string => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 && !in_string {
+                return Ok(value);
+            }
+        }
+    }
+}
@@ -2,40 +2,168 @@
 #![deny(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
+mod audacity;
+mod cue;
+mod description;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod ffmetadata;
+#[cfg(feature = "hls")]
+mod hls;
+mod html;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+mod json_stream;
+mod markers;
+#[cfg(feature = "mp3")]
+mod mp3;
+mod mp4;
+mod psc;
+#[cfg(feature = "schema")]
+mod schema;
+#[cfg(feature = "json")]
 mod serialization;
+mod srt;
+#[cfg(feature = "toml")]
+mod toml;
+mod vorbis;
+
+#[cfg(feature = "toml")]
+pub use crate::toml::{from_toml, to_toml};
+pub use audacity::{from_audacity_labels, to_audacity_labels};
+pub use cue::{from_cue, to_cue};
+pub use description::{
+    detect_description_format, format_timestamp, from_description, from_description_verbose,
+    from_description_with_options, parse_timestamp, to_description, to_description_with_links,
+    to_description_with_options, to_markdown, DescriptionOptions, HourFormatting,
+    MissingTitlePolicy, Padding, ParseWarning, ParseWarningReason, Rounding, TimestampFormat,
+    ToDescriptionOptions,
+};
+#[cfg(feature = "fetch")]
+pub use fetch::from_chapters_url;
+pub use ffmetadata::{from_ffmetadata, to_ffmetadata};
+#[cfg(feature = "hls")]
+pub use hls::{from_hls_playlist, to_hls_dateranges};
+pub use html::from_description_html;
+#[cfg(feature = "json")]
+pub use json::{
+    from_json, from_json_strict, from_json_with_base, from_ndjson, to_json, to_json_compact,
+    to_json_with_options, to_json_with_version, to_ndjson, write_json, JsonOptions,
+    PodcastNamespaceChapter, PodcastNamespaceChapters,
+};
+#[cfg(feature = "json")]
+pub use json_stream::iter_json;
+pub use markers::from_marker_tsv;
+#[cfg(feature = "tokio")]
+pub use mp3::from_mp3_file_async;
+#[cfg(feature = "mp3")]
+pub use mp3::{
+    from_mp3_file, from_mp3_file_ext, from_mp3_file_with_options, to_mp3_file,
+    to_mp3_file_with_options, to_mp3_file_with_version, Mp3Chapters, Mp3Options, Mp3WriteOptions,
+};
+pub use mp4::{from_mp4_file, to_mp4_file};
+pub use psc::{from_psc, to_psc};
+#[cfg(feature = "schema")]
+pub use schema::validate_json_against_schema;
+pub use srt::{from_srt, from_webvtt};
+pub use vorbis::{from_opus_file, to_flac_file};
 
 use chrono::Duration;
-use id3::{Error, ErrorKind, Tag, TagLike, Version};
+#[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
-use std::path::Path;
 #[cfg(feature = "rssblue")]
 use uuid::Uuid;
 
 /// Represents a web link for the [chapter](crate::Chapter).
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize))]
 pub struct Link {
     /// The URL of the link.
-    #[serde(serialize_with = "serialization::url_to_string")]
+    #[cfg_attr(
+        feature = "json",
+        serde(serialize_with = "serialization::url_to_string")
+    )]
     pub url: url::Url,
     /// The title of the link.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
     pub title: Option<String>,
 }
 
 /// Represents a [chapter](crate::Chapter) image.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
 pub enum Image {
-    /// The URL of the image.
-    Url(url::Url),
-    // TODO: some ways of encoding chapters (e.g., ID3 tags in MP3 files) allow to embed images directly in the file.
-    // Data(Vec<u8>),
+    /// Image given by its URL, optionally with metadata that lets players pick or lay out the
+    /// image before fetching it.
+    Url {
+        /// The URL of the image.
+        url: url::Url,
+        /// The MIME type of the image, e.g., "image/jpeg".
+        mime_type: Option<String>,
+        /// The width of the image in pixels.
+        width: Option<u32>,
+        /// The height of the image in pixels.
+        height: Option<u32>,
+    },
+    /// Image embedded directly in the file, e.g., in an MP3 file's ID3 tag.
+    Data {
+        /// The MIME type of the image, e.g., "image/jpeg".
+        mime_type: String,
+        /// The image bytes.
+        data: Vec<u8>,
+    },
+}
+
+impl From<url::Url> for Image {
+    /// Creates an [Image::Url] without any metadata.
+    fn from(url: url::Url) -> Self {
+        Self::Url {
+            url,
+            mime_type: None,
+            width: None,
+            height: None,
+        }
+    }
+}
+
+/// Represents a location tied to a [chapter](crate::Chapter), as defined in the [Podcast namespace
+/// specification](https://github.com/Podcastindex-org/podcast-namespace/blob/main/location/location.md).
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Location {
+    /// Human-readable name of the place, e.g., "Austin, TX".
+    pub name: String,
+    /// Latitude and longitude of the place in the [geo URI](https://en.wikipedia.org/wiki/Geo_URI_scheme)
+    /// form, e.g., "geo:30.2672,97.7431".
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub geo: Option<String>,
+    /// [OpenStreetMap](https://www.openstreetmap.org) object of the place in the form of type
+    /// (`N`ode, `W`ay, or `R`elation) followed by the ID, e.g., "R113314".
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub osm: Option<String>,
+}
+
+#[cfg(feature = "json")]
+impl Location {
+    /// Returns the latitude and longitude from the `geo` URI, if present.
+    fn coordinates(&self) -> Option<Result<(f64, f64), String>> {
+        let geo = self.geo.as_ref()?;
+        let parse = || -> Option<(f64, f64)> {
+            let coordinates = geo.strip_prefix("geo:")?.split(';').next()?;
+            let mut parts = coordinates.split(',');
+            let latitude = parts.next()?.trim().parse().ok()?;
+            let longitude = parts.next()?.trim().parse().ok()?;
+            Some((latitude, longitude))
+        };
+        Some(parse().ok_or_else(|| format!("Invalid geo URI `{}`", geo)))
+    }
 }
 
 /// Represents a remote item as defined in the [Podcast namespace
 /// specification](https://podcastindex.org/namespace/1.0#remote-item). Used internally by RSS
 /// Blue.
 #[cfg(feature = "rssblue")]
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Clone)]
 pub enum RemoteEntity {
     /// Represents a podcast feed.
     #[serde(rename = "feed")]
@@ -54,32 +182,66 @@ pub enum RemoteEntity {
 }
 
 /// Chapters follow mostly the [Podcast namespace specification](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
-#[derive(Debug, PartialEq, Serialize)]
+///
+/// Two chapters are equal if and only if all of their fields are equal, so, e.g., chapters gathered
+/// from several feeds can be deduplicated with a [HashSet](std::collections::HashSet). Embedded
+/// [images](Image::Data) are compared and hashed by their bytes.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "json", derive(Serialize))]
 pub struct Chapter {
     /// The starting time of the chapter.
-    #[serde(serialize_with = "serialization::duration_to_float")]
+    #[cfg_attr(
+        feature = "json",
+        serde(serialize_with = "serialization::duration_to_float")
+    )]
     pub start: Duration,
     /// The end time of the chapter.
-    #[serde(
-        serialize_with = "serialization::duration_option_to_float_option",
-        skip_serializing_if = "Option::is_none"
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "serialization::duration_option_to_float_option",
+            skip_serializing_if = "Option::is_none"
+        )
     )]
     pub end: Option<Duration>,
     /// The title of this chapter.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
     pub title: Option<String>,
+    /// Language of the chapter's title and other metadata as a [BCP 47](https://www.rfc-editor.org/info/bcp47)
+    /// tag, e.g., "en" or "pt-BR", for podcasts that are published in several languages.
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub language: Option<String>,
     /// The image to use as chapter art.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
     pub image: Option<Image>,
+    /// Additional chapter art besides [image](crate::Chapter::image), e.g., a full-resolution
+    /// version of a thumbnail, so that players can pick the one that fits best.
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub images: Vec<Image>,
     /// Web page or supporting document that's related to the topic of this chapter.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
     pub link: Option<Link>,
     /// If this property is set to true, this chapter should not display visibly to the user in either the table of contents or as a jump-to point in the user interface. In the original spec, the inverse of this is called `toc`.
     pub hidden: bool,
-    // TODO: This object defines an optional location that is tied to this chapter.
-    // pub location: Option<()>,
+    /// Location that is tied to this chapter.
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub location: Option<Location>,
+    /// Element ID of the chapter's [ID3](https://en.wikipedia.org/wiki/ID3) `CHAP` frame, e.g.,
+    /// "chp1". It is read from MP3 files and reused when writing them, so that the ID stays stable
+    /// across edits. The ID "toc" is reserved for the table of contents.
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub element_id: Option<String>,
+    /// Byte offset of the chapter's first audio frame from the beginning of the file, which some
+    /// players use for seeking in VBR MP3 files. It is read from and written to ID3 `CHAP` frames,
+    /// where a missing offset is stored as `0xFFFFFFFF`.
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub start_byte: Option<u32>,
+    /// Byte offset of the first byte after the chapter's last audio frame; see
+    /// [start_byte](crate::Chapter::start_byte).
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    pub end_byte: Option<u32>,
     /// Remote entity used internally by RSS Blue.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
     #[cfg(feature = "rssblue")]
     pub remote_entity: Option<RemoteEntity>,
 }
@@ -90,770 +252,2103 @@ impl Default for Chapter {
             start: Duration::zero(),
             end: None,
             title: None,
+            language: None,
             image: None,
+            images: Vec::new(),
             link: None,
             hidden: false,
+            location: None,
+            element_id: None,
+            start_byte: None,
+            end_byte: None,
             #[cfg(feature = "rssblue")]
             remote_entity: None,
         }
     }
 }
 
-impl From<PodcastNamespaceChapter> for Chapter {
-    fn from(podcast_namespace_chapter: PodcastNamespaceChapter) -> Self {
-        Self {
-            start: podcast_namespace_chapter.start_time,
-            end: podcast_namespace_chapter.end_time,
-            title: podcast_namespace_chapter.title,
-            image: podcast_namespace_chapter.img.map(Image::Url),
-            link: podcast_namespace_chapter
-                .url
-                .map(|url| Link { url, title: None }),
-            hidden: !podcast_namespace_chapter.toc.unwrap_or(true),
-            #[cfg(feature = "rssblue")]
-            remote_entity: podcast_namespace_chapter.remote_entity,
-        }
-    }
-}
-
-/// Chapters of the [Podcast namespace](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
-pub struct PodcastNamespaceChapters {
-    version: String,
-    chapters: Vec<PodcastNamespaceChapter>,
-}
-
-impl From<&[Chapter]> for PodcastNamespaceChapters {
-    fn from(chapters: &[Chapter]) -> Self {
-        Self {
-            version: "1.2.0".to_string(),
-            chapters: chapters.iter().map(|c| c.into()).collect(),
-        }
-    }
-}
-
-/// Individual chapter inside [Chapters](crate::PodcastNamespaceChapters).
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PodcastNamespaceChapter {
-    /// The starting time of the chapter.
-    #[serde(
-        deserialize_with = "serialization::float_to_duration",
-        serialize_with = "serialization::duration_to_float"
-    )]
-    start_time: Duration,
-    /// The end time of the chapter.
-    #[serde(
-        default,
-        deserialize_with = "serialization::float_to_duration_option",
-        serialize_with = "serialization::duration_option_to_float_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    end_time: Option<Duration>,
-    /// The title of this chapter.
-    #[serde(default)]
-    title: Option<String>,
-    /// The url of an image to use as chapter art.
-    #[serde(
-        default,
-        deserialize_with = "serialization::string_to_url",
-        serialize_with = "serialization::url_option_to_string",
-        skip_serializing_if = "Option::is_none"
-    )]
-    img: Option<url::Url>,
-    /// The url of a web page or supporting document that's related to the topic of this chapter.
-    #[serde(
-        default,
-        deserialize_with = "serialization::string_to_url",
-        serialize_with = "serialization::url_option_to_string",
-        skip_serializing_if = "Option::is_none"
-    )]
-    url: Option<url::Url>,
-    /// If this property is present and set to false, this chapter should not display visibly to the user in either the table of contents or as a jump-to point in the user interface.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    toc: Option<bool>,
-    // TODO: This object defines an optional location that is tied to this chapter.
-    // pub location: Option<()>,
-    #[cfg(feature = "rssblue")]
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        rename = "rssblue:remoteEntity"
-    )]
-    remote_entity: Option<RemoteEntity>,
-}
-
-impl<'a> From<&'a Chapter> for PodcastNamespaceChapter {
-    fn from(chapter: &'a Chapter) -> Self {
-        Self {
-            start_time: chapter.start,
-            end_time: chapter.end,
-            title: chapter.title.clone(),
-            img: match &chapter.image {
-                Some(Image::Url(url)) => Some(url.clone()),
-                _ => None,
-            },
-            url: chapter.link.as_ref().map(|link| link.url.clone()),
-            toc: if chapter.hidden { Some(false) } else { None },
-            #[cfg(feature = "rssblue")]
-            remote_entity: chapter.remote_entity.clone(),
-        }
-    }
-}
-
-/// Reads [chapters](crate::Chapter) from a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
+/// Chapters are ordered by timing: by [start](Chapter::start), with ties broken by
+/// [title](Chapter::title), so that sorting is deterministic.
+///
+/// The remaining fields only decide the order of chapters that have the same start and title,
+/// which keeps the ordering consistent with equality.
 ///
 /// # Example:
 /// ```rust
-/// # use chapters::{Chapter, Image, Link};
+/// # use chapters::Chapter;
 /// # use chrono::Duration;
 /// # use pretty_assertions::assert_eq;
 /// #
 /// # fn main() {
-/// let json = r#"{
-///   "version": "1.2.0",
-///   "chapters": [
-///     {
-///       "startTime": 0,
-///       "endTime": 30.5,
-///       "title": "Chapter 1",
-///       "img": "https://example.com/chapter-1.jpg",
-///       "url": "https://example.com/chapter-1"
+/// let mut chapters = vec![
+///     Chapter {
+///         start: Duration::seconds(30),
+///         title: Some("Baboons".to_string()),
+///         ..Default::default()
 ///     },
-///     {
-///       "startTime": 30.5,
-///       "title": "Chapter 2"
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
 ///     },
-///     {
-///       "startTime": 55,
-///       "title": "Hidden chapter",
-///       "toc": false
+///     Chapter {
+///         start: Duration::seconds(30),
+///         title: Some("Ad".to_string()),
+///         ..Default::default()
 ///     },
-///     {
-///       "startTime": 60,
-///       "endTime": 90,
-///       "title": "Chapter 3",
-///       "img": "https://example.com/chapter-3.jpg"
-///     }
-///   ]
-/// }"#;
+/// ];
 ///
-/// let chapters = chapters::from_json(json.as_bytes()).unwrap();
+/// chapters.sort();
 ///
-/// assert_eq!(
-///     chapters,
-///     vec![
-///         Chapter {
-///             start: Duration::seconds(0),
-///             end: Some(Duration::seconds(30) + Duration::milliseconds(500)),
-///             title: Some("Chapter 1".to_string()),
-///             image: Some(Image::Url(
-///                 url::Url::parse("https://example.com/chapter-1.jpg").unwrap()
-///             )),
-///             link: Some(Link {
-///                 url: url::Url::parse("https://example.com/chapter-1").unwrap(),
-///                 title: None,
-///             }),
-///             ..Default::default()
-///         },
-///         Chapter {
-///             start: Duration::seconds(30) + Duration::milliseconds(500),
-///             end: None,
-///             title: Some("Chapter 2".to_string()),
-///             ..Default::default()
-///         },
-///         Chapter {
-///             start: Duration::seconds(55),
-///             end: None,
-///             title: Some("Hidden chapter".to_string()),
-///             hidden: true,
-///             ..Default::default()
-///         },
-///         Chapter {
-///             start: Duration::seconds(60),
-///             end: Some(Duration::seconds(90)),
-///             title: Some("Chapter 3".to_string()),
-///             image: Some(Image::Url(
-///                 url::Url::parse("https://example.com/chapter-3.jpg").unwrap()
-///             )),
-///             ..Default::default()
-///         },
-///     ]
-/// );
+/// let titles: Vec<_> = chapters.iter().map(|c| c.title.as_deref().unwrap()).collect();
+/// assert_eq!(titles, vec!["Intro", "Ad", "Baboons"]);
 /// # }
 /// ```
-pub fn from_json<R: std::io::Read>(reader: R) -> Result<Vec<Chapter>, String> {
-    let podcast_namespace_chapters: PodcastNamespaceChapters =
-        serde_json::from_reader(reader).map_err(|e| e.to_string())?;
-    Ok(podcast_namespace_chapters
-        .chapters
-        .into_iter()
-        .map(|c| c.into())
-        .collect())
+impl Ord for Chapter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let ordering = self
+            .start
+            .cmp(&other.start)
+            .then_with(|| self.title.cmp(&other.title))
+            .then_with(|| self.end.cmp(&other.end))
+            .then_with(|| self.language.cmp(&other.language))
+            .then_with(|| self.image.cmp(&other.image))
+            .then_with(|| self.images.cmp(&other.images))
+            .then_with(|| self.link.cmp(&other.link))
+            .then_with(|| self.hidden.cmp(&other.hidden))
+            .then_with(|| self.location.cmp(&other.location))
+            .then_with(|| self.element_id.cmp(&other.element_id))
+            .then_with(|| self.start_byte.cmp(&other.start_byte))
+            .then_with(|| self.end_byte.cmp(&other.end_byte));
+        #[cfg(feature = "rssblue")]
+        let ordering = ordering.then_with(|| self.remote_entity.cmp(&other.remote_entity));
+
+        ordering
+    }
 }
 
-/// Writes [chapters](crate::Chapter) to a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md).
-///
-/// # Example:
-/// ```rust
-/// # use chapters::{Chapter, Image, Link};
-/// # use chrono::Duration;
-/// # use pretty_assertions::assert_eq;
-/// #
-/// # fn main() {
-/// let chapters = vec![
-///    Chapter {
-///        start: Duration::zero(),
-///        title: Some("Chapter 1".to_string()),
-///        ..Default::default()
-///    },
-///    Chapter {
-///        start: Duration::seconds(45) + Duration::milliseconds(900),
-///        title: Some("Chapter 2".to_string()),
-///        link: Some(Link {
-///            url: "https://example.com".parse().unwrap(),
-///            title: Some("Example".to_string()),
-///        }),
-///        ..Default::default()
-///    },
-///    Chapter {
-///        start: Duration::minutes(1)+Duration::seconds(5),
-///        title: Some("Hidden chapter".to_string()),
-///        hidden: true,
-///        ..Default::default()
-///    },
-///    Chapter {
-///        start: Duration::minutes(2)+Duration::seconds(10)+Duration::milliseconds(500),
-///        title: Some("Chapter 3".to_string()),
-///        image: Some(Image::Url("https://example.com/image.png".parse().unwrap())),
-///        ..Default::default()
-///    },
-/// ];
-///
-/// let json_chapters = chapters::to_json(&chapters).expect("Failed to serialize chapters");
-///
-/// assert_eq!(json_chapters, r#"{
-///   "version": "1.2.0",
-///   "chapters": [
-///     {
-///       "startTime": 0,
-///       "title": "Chapter 1"
-///     },
-///     {
-///       "startTime": 45.9,
-///       "title": "Chapter 2",
-///       "url": "https://example.com/"
-///     },
-///     {
-///       "startTime": 65,
-///       "title": "Hidden chapter",
-///       "toc": false
-///     },
-///     {
-///       "startTime": 130.5,
-///       "title": "Chapter 3",
-///       "img": "https://example.com/image.png"
-///     }
-///   ]
-/// }"#);
-/// # }
-/// ```
-pub fn to_json(chapters: &[Chapter]) -> Result<String, String> {
-    let podcast_namespace_chapters: PodcastNamespaceChapters = chapters.into();
-    serde_json::to_string_pretty(&podcast_namespace_chapters).map_err(|e| e.to_string())
-}
-
-/// Timestamp format used in episode descriptions.
-#[derive(Debug, Clone)]
-enum TimestampType {
-    /// MM:SS format, e.g., "12:34"
-    MmSs,
-    /// HH:MM:SS format, e.g., "01:23:45"
-    HhMmSs,
-    /// MM:SS format within parentheses, e.g., "(12:34)"
-    MmSsParentheses,
-    /// HH:MM:SS format within parentheses, e.g., "(01:23:45)"
-    HhMmSsParentheses,
-}
-
-impl TimestampType {
-    fn regex_pattern(&self) -> &str {
-        match self {
-            Self::MmSs => r"^(?P<minutes>[0-5]\d):(?P<seconds>[0-5]\d)",
-            Self::HhMmSs => r"^(?P<hours>\d{2}):(?P<minutes>[0-5]\d):(?P<seconds>[0-5]\d)",
-            Self::MmSsParentheses => r"^\((?P<minutes>[0-5]\d):(?P<seconds>[0-5]\d)\)",
-            Self::HhMmSsParentheses => {
-                r"^\((?P<hours>\d{2}):(?P<minutes>[0-5]\d):(?P<seconds>[0-5]\d)\)"
-            }
+impl PartialOrd for Chapter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Chapter {
+    /// Returns the time at which this chapter ends.
+    ///
+    /// This is the chapter's own end time if it is set, otherwise the start of the `next` chapter,
+    /// or `total` (the length of the episode) if this is the last chapter.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// # use chrono::Duration;
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let first = Chapter {
+    ///     start: Duration::zero(),
+    ///     ..Default::default()
+    /// };
+    /// let second = Chapter {
+    ///     start: Duration::seconds(30),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(first.implied_end(Some(&second), None), Some(Duration::seconds(30)));
+    /// assert_eq!(second.implied_end(None, None), None);
+    /// assert_eq!(
+    ///     second.implied_end(None, Some(Duration::minutes(1))),
+    ///     Some(Duration::minutes(1))
+    /// );
+    /// # }
+    /// ```
+    pub fn implied_end(&self, next: Option<&Chapter>, total: Option<Duration>) -> Option<Duration> {
+        self.end.or(next.map(|next| next.start)).or(total)
+    }
+
+    /// Checks whether the `next` chapter starts within `tolerance` of where this chapter ends.
+    ///
+    /// The end of this chapter is its [implied end](crate::Chapter::implied_end), so a chapter
+    /// without an end time is always adjacent to the chapter that follows it. This is useful to
+    /// tell intentional gaps between chapters from rounding noise.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// # use chrono::Duration;
+    /// #
+    /// # fn main() {
+    /// let first = Chapter {
+    ///     start: Duration::zero(),
+    ///     end: Some(Duration::milliseconds(29_990)),
+    ///     ..Default::default()
+    /// };
+    /// let second = Chapter {
+    ///     start: Duration::seconds(30),
+    ///     ..Default::default()
+    /// };
+    /// let third = Chapter {
+    ///     start: Duration::seconds(45),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!(first.is_adjacent_to(&second, Duration::milliseconds(50)));
+    /// assert!(!first.is_adjacent_to(&third, Duration::milliseconds(50)));
+    /// # }
+    /// ```
+    pub fn is_adjacent_to(&self, next: &Chapter, tolerance: Duration) -> bool {
+        let end = self.end.unwrap_or(next.start);
+        (next.start - end).abs() <= tolerance
+    }
+
+    /// Checks whether `time` falls within this chapter.
+    ///
+    /// The chapter covers the time from its start up to, but not including, its end, which is its
+    /// own end time if set, otherwise `next_start` (the start of the following chapter). Without
+    /// either, the chapter runs until the end of the episode.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// # use chrono::Duration;
+    /// #
+    /// # fn main() {
+    /// let chapter = Chapter::at_seconds(30.0, "Baboons");
+    /// let next_start = Some(Duration::minutes(1));
+    ///
+    /// assert!(chapter.contains(Duration::seconds(30), next_start));
+    /// assert!(!chapter.contains(Duration::seconds(29), next_start));
+    /// assert!(!chapter.contains(Duration::minutes(1), next_start));
+    /// assert!(chapter.contains(Duration::minutes(1), None));
+    /// # }
+    /// ```
+    pub fn contains(&self, time: Duration, next_start: Option<Duration>) -> bool {
+        self.start <= time && self.end.or(next_start).is_none_or(|end| time < end)
+    }
+
+    /// Returns a [builder](crate::ChapterBuilder) for a chapter starting at zero.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::{Chapter, Link};
+    /// # use chrono::Duration;
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let chapter = Chapter::builder()
+    ///     .start(Duration::seconds(30))
+    ///     .title("Chapter 2")
+    ///     .link_url(url::Url::parse("https://example.com").unwrap())
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     chapter,
+    ///     Chapter {
+    ///         start: Duration::seconds(30),
+    ///         title: Some("Chapter 2".to_string()),
+    ///         link: Some(Link {
+    ///             url: url::Url::parse("https://example.com").unwrap(),
+    ///             title: None,
+    ///         }),
+    ///         ..Default::default()
+    ///     }
+    /// );
+    /// # }
+    /// ```
+    pub fn builder() -> ChapterBuilder {
+        ChapterBuilder::default()
+    }
+
+    /// Creates a chapter with the given title starting at `seconds`, rounded to the nearest
+    /// millisecond.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// # use chrono::Duration;
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let chapter = Chapter::at_seconds(90.5, "Chapter 2");
+    ///
+    /// assert_eq!(chapter.start, Duration::milliseconds(90_500));
+    /// assert_eq!(chapter.title, Some("Chapter 2".to_string()));
+    /// # }
+    /// ```
+    pub fn at_seconds(seconds: f64, title: impl Into<String>) -> Self {
+        Self {
+            start: Duration::milliseconds((seconds * 1000.0).round() as i64),
+            title: Some(title.into()),
+            ..Default::default()
         }
     }
 
-    fn line_regex_pattern(&self) -> String {
-        // Combines the timestamp regex pattern with space (or a punctuation mark) and a pattern for text following the timestamp.
-        format!("{}[.!?\\- ]+(?P<text>.+)$", self.regex_pattern())
-    }
-
-    fn from_line(line: &str) -> Option<Self> {
-        if let Some(first_char) = line.chars().next() {
-            // regex can be expensive, so we first check if the line at least starts with the right character.
-            if first_char == '(' || first_char.is_numeric() {
-                return [
-                    Self::MmSs,
-                    Self::HhMmSs,
-                    Self::MmSsParentheses,
-                    Self::HhMmSsParentheses,
-                ]
-                .iter()
-                .find(|&temp_timestamp_type| {
-                    regex::Regex::new(temp_timestamp_type.line_regex_pattern().as_str())
-                        .map(|re| re.captures(line).is_some())
-                        .unwrap_or(false)
-                })
-                .cloned();
-            }
+    /// Creates a marker, i.e., a chapter with no [end](crate::Chapter::end) and an optional title,
+    /// at the given time.
+    ///
+    /// Chapters are always anchored at their start, so a point in the episode such as "credits
+    /// begin" is a marker starting there. Untitled markers are written without a title where the
+    /// format allows it, e.g., JSON omits the `title` key, while formats that need one, such as
+    /// [episode descriptions](crate::to_description), return an error unless a
+    /// [policy](crate::MissingTitlePolicy) for untitled chapters is given.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// # use chrono::Duration;
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let credits = Chapter::marker(Duration::minutes(42), None);
+    ///
+    /// assert_eq!(credits.start, Duration::minutes(42));
+    /// assert_eq!(credits.end, None);
+    /// assert_eq!(credits.title, None);
+    /// assert_eq!(
+    ///     chapters::to_description(&[credits]),
+    ///     Err("Chapter 0 has no title".to_string())
+    /// );
+    /// # }
+    /// ```
+    pub fn marker(at: Duration, title: Option<String>) -> Self {
+        Self {
+            start: at,
+            title,
+            ..Default::default()
         }
-        None
+    }
+
+    /// Returns the starting time of the chapter in seconds.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// #
+    /// # fn main() {
+    /// assert_eq!(Chapter::at_seconds(12.25, "Intro").start_seconds(), 12.25);
+    /// # }
+    /// ```
+    pub fn start_seconds(&self) -> f64 {
+        self.start.num_milliseconds() as f64 / 1000.0
+    }
+
+    /// Returns the end time of the chapter in seconds, if it is set.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::Chapter;
+    /// # use chrono::Duration;
+    /// #
+    /// # fn main() {
+    /// let mut chapter = Chapter::at_seconds(0.0, "Intro");
+    /// assert_eq!(chapter.end_seconds(), None);
+    ///
+    /// chapter.end = Some(Duration::milliseconds(4_500));
+    /// assert_eq!(chapter.end_seconds(), Some(4.5));
+    /// # }
+    /// ```
+    pub fn end_seconds(&self) -> Option<f64> {
+        self.end.map(|end| end.num_milliseconds() as f64 / 1000.0)
+    }
+
+    /// Returns the chapter's main image: its [image](crate::Chapter::image) if set, otherwise the
+    /// first of its additional [images](crate::Chapter::images).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::{Chapter, Image};
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let thumbnail = Image::from(url::Url::parse("https://example.com/thumbnail.jpg").unwrap());
+    /// let full = Image::from(url::Url::parse("https://example.com/full.jpg").unwrap());
+    ///
+    /// let mut chapter = Chapter {
+    ///     images: vec![full.clone()],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(chapter.primary_image(), Some(&full));
+    ///
+    /// chapter.image = Some(thumbnail.clone());
+    /// assert_eq!(chapter.primary_image(), Some(&thumbnail));
+    /// # }
+    /// ```
+    pub fn primary_image(&self) -> Option<&Image> {
+        self.image.as_ref().or_else(|| self.images.first())
     }
 }
 
-/// Reads [chapters](crate::Chapter) from [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes).
-///
-/// # Example:
-/// ```rust
-/// # use pretty_assertions::assert_eq;
-/// #
-/// # fn main() {
-/// let description = r#"
-/// In this episode, we explore a hot new trend in fitness: "The Movement"!
-///
-/// 00:00 - The Movement
-/// 05:04 - Baboons
-/// 09:58 - Steve Jobs
-/// "#;
-///
-/// let chapters = chapters::from_description(description).expect("Failed to parse chapters");
+/// Builds a [chapter](crate::Chapter) step by step; see [Chapter::builder].
 ///
-/// assert_eq!(chapters.len(), 3);
-/// assert_eq!(chapters[1].title, Some(String::from("Baboons")));
-/// # }
-/// ```
-pub fn from_description(description: &str) -> Result<Vec<Chapter>, String> {
-    let mut chapters = Vec::new();
-    let mut timestamp_type: Option<TimestampType> = None;
-
-    let parse_line = |line: &str, timestamp_type: &TimestampType| -> Option<Chapter> {
-        let re = regex::Regex::new(timestamp_type.line_regex_pattern().as_str())
-            .map_err(|e| e.to_string())
-            .ok()?;
-
-        if let Some(captures) = re.captures(line) {
-            let start = parse_timestamp(&captures).ok()?;
-            let text = captures.name("text").unwrap().as_str();
-            Some(Chapter {
-                start,
-                end: None,
-                title: Some(text.trim().to_string()),
-                image: None,
-                link: None,
-                hidden: false,
-                #[cfg(feature = "rssblue")]
-                remote_entity: None,
-            })
-        } else {
-            None
-        }
-    };
+/// Fields that are not set keep their [default](crate::Chapter::default) values, so a chapter
+/// without an explicit start begins at zero.
+#[derive(Debug, Default, Clone)]
+pub struct ChapterBuilder {
+    chapter: Chapter,
+}
 
-    for line in description.lines().map(|line| line.trim()) {
-        if timestamp_type.is_none() {
-            timestamp_type = TimestampType::from_line(line);
-        }
+impl ChapterBuilder {
+    /// Sets the starting time of the chapter.
+    pub fn start(mut self, start: Duration) -> Self {
+        self.chapter.start = start;
+        self
+    }
 
-        if let Some(timestamp_type) = timestamp_type.as_ref() {
-            if let Some(chapter) = parse_line(line, timestamp_type) {
-                chapters.push(chapter);
-            } else {
-                break;
-            }
-        }
+    /// Sets the end time of the chapter.
+    pub fn end(mut self, end: Duration) -> Self {
+        self.chapter.end = Some(end);
+        self
+    }
+
+    /// Sets the title of the chapter.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.chapter.title = Some(title.into());
+        self
+    }
+
+    /// Sets the URL of the chapter's link, without a link title.
+    pub fn link_url(mut self, url: url::Url) -> Self {
+        self.chapter.link = Some(Link { url, title: None });
+        self
+    }
+
+    /// Sets the URL of the chapter's image.
+    pub fn image_url(mut self, url: url::Url) -> Self {
+        self.chapter.image = Some(Image::from(url));
+        self
+    }
+
+    /// Sets whether the chapter is [hidden](crate::Chapter::hidden).
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.chapter.hidden = hidden;
+        self
     }
 
-    Ok(chapters)
+    /// Returns the built chapter.
+    pub fn build(self) -> Chapter {
+        self.chapter
+    }
 }
 
-/// Writes [chapters](crate::Chapter) to [episode description](https://help.spotifyforpodcasters.com/hc/en-us/articles/13194991130779-Enabling-podcast-chapters-) (show notes).
+/// A list of [chapters](crate::Chapter) with convenience methods.
 ///
-/// Only the start time and title are used.
+/// It dereferences to a slice of chapters, so it can be passed to functions such as
+/// [validate] or [to_json] as is.
 ///
 /// # Example:
 /// ```rust
-/// # use chapters::{Chapter, Link};
-/// # use chrono::Duration;
-/// # use pretty_assertions::assert_eq;
+/// # use chapters::{Chapter, Chapters};
 /// #
 /// # fn main() {
-/// let chapters = vec![
-///     Chapter {
-///         start: Duration::zero(),
-///         title: Some("The Movement".to_string()),
-///         link: Some(Link {
-///             url: url::Url::parse("https://example.com/the-movement").unwrap(),
-///             title: None,
-///         }),
-///         ..Default::default()
-///     },
-///     Chapter {
-///         start: Duration::minutes(5) + Duration::seconds(4),
-///         title: Some("Baboons".to_string()),
-///         ..Default::default()
-///     },
-///     Chapter {
-///         start: Duration::minutes(9) + Duration::seconds(58),
-///         title: Some("Steve Jobs".to_string()),
-///         ..Default::default()
-///     },
-/// ];
+/// let chapters: Chapters = vec![
+///     Chapter::at_seconds(0.0, "Intro"),
+///     Chapter::at_seconds(30.0, "Baboons"),
+/// ]
+/// .into_iter()
+/// .collect();
 ///
-/// let description = chapters::to_description(&chapters).expect("Failed to write chapters");
-/// assert_eq!(
-///     description,
-///     r#"00:00 The Movement
-/// 05:04 Baboons
-/// 09:58 Steve Jobs
-/// "#
-/// );
+/// assert_eq!(chapters.len(), 2);
+/// assert!(chapters::validate(&chapters).is_ok());
 /// # }
-///    ```
-pub fn to_description(chapters: &[Chapter]) -> Result<String, String> {
-    let mut description = String::new();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Chapters(pub Vec<Chapter>);
 
-    let at_least_an_hour = chapters
-        .iter()
-        .any(|chapter| chapter.start >= Duration::hours(1));
-    let timestamp_type = if at_least_an_hour {
-        TimestampType::HhMmSs
-    } else {
-        TimestampType::MmSs
-    };
+impl Chapters {
+    /// Returns the chapter that is playing at `time`.
+    ///
+    /// This is the chapter found by [active_chapter]: a chapter is playing from its start until
+    /// its [implied end](crate::Chapter::implied_end), the last chapter without an end time is
+    /// playing until the end of the episode, and where chapters overlap, the later one wins. The
+    /// chapters are expected to be sorted by start time.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::{Chapter, Chapters};
+    /// # use chrono::Duration;
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let chapters = Chapters(vec![
+    ///     Chapter {
+    ///         start: Duration::seconds(5),
+    ///         end: Some(Duration::seconds(20)),
+    ///         title: Some("Intro".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     Chapter::at_seconds(30.0, "Baboons"),
+    /// ]);
+    ///
+    /// assert_eq!(chapters.at(Duration::zero()), None);
+    /// assert_eq!(chapters.at(Duration::seconds(10)), Some(&chapters[0]));
+    /// assert_eq!(chapters.at(Duration::seconds(25)), None);
+    /// assert_eq!(chapters.at(Duration::minutes(10)), Some(&chapters[1]));
+    ///
+    /// // A chapter nested in a longer one.
+    /// let chapters = Chapters(vec![
+    ///     Chapter {
+    ///         start: Duration::zero(),
+    ///         end: Some(Duration::minutes(1)),
+    ///         title: Some("Interview".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     Chapter {
+    ///         start: Duration::seconds(10),
+    ///         end: Some(Duration::seconds(20)),
+    ///         title: Some("Baboons".to_string()),
+    ///         ..Default::default()
+    ///     },
+    /// ]);
+    ///
+    /// assert_eq!(chapters.at(Duration::seconds(15)), Some(&chapters[1]));
+    /// assert_eq!(chapters.at(Duration::seconds(30)), Some(&chapters[0]));
+    /// # }
+    /// ```
+    pub fn at(&self, time: Duration) -> Option<&Chapter> {
+        active_chapter(&self.0, time).map(|index| &self.0[index])
+    }
 
-    for chapter in chapters {
-        let start = chapter.start;
-        let title = chapter.title.as_ref().ok_or("Chapter title is missing")?;
-        let line = format!(
-            "{} {}",
-            duration_to_timestamp(start, timestamp_type.clone()),
-            title
-        );
-        description.push_str(&line);
-        description.push('\n');
+    /// Returns the latest end time of any chapter, or [None] if no chapter has an end time.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::{Chapter, Chapters};
+    /// # use chrono::Duration;
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let mut chapters = Chapters(vec![
+    ///     Chapter::at_seconds(0.0, "Intro"),
+    ///     Chapter::at_seconds(30.0, "Baboons"),
+    /// ]);
+    /// assert_eq!(chapters.duration(), None);
+    ///
+    /// chapters.0[1].end = Some(Duration::minutes(1));
+    /// assert_eq!(chapters.duration(), Some(Duration::minutes(1)));
+    /// # }
+    /// ```
+    pub fn duration(&self) -> Option<Duration> {
+        self.0.iter().filter_map(|chapter| chapter.end).max()
     }
 
-    Ok(description)
+    /// Returns the chapters that are not [hidden](crate::Chapter::hidden).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use chapters::{Chapter, Chapters};
+    /// # use pretty_assertions::assert_eq;
+    /// #
+    /// # fn main() {
+    /// let mut chapters = Chapters(vec![
+    ///     Chapter::at_seconds(0.0, "Intro"),
+    ///     Chapter::at_seconds(30.0, "Baboons"),
+    /// ]);
+    /// chapters.0[0].hidden = true;
+    ///
+    /// assert_eq!(chapters.visible().collect::<Vec<_>>(), vec![&chapters[1]]);
+    /// # }
+    /// ```
+    pub fn visible(&self) -> impl Iterator<Item = &Chapter> {
+        self.0.iter().filter(|chapter| !chapter.hidden)
+    }
 }
 
-fn parse_timestamp(captures: &regex::Captures) -> Result<Duration, String> {
-    let parse_i64 = |capture: Option<regex::Match>| -> Result<i64, String> {
-        capture
-            .map(|m| m.as_str().parse::<i64>().map_err(|e| e.to_string()))
-            .unwrap_or(Ok(0))
-    };
-
-    let hours = parse_i64(captures.name("hours"))?;
-    let minutes = parse_i64(captures.name("minutes"))?;
-    let seconds = parse_i64(captures.name("seconds"))?;
+impl std::ops::Deref for Chapters {
+    type Target = [Chapter];
 
-    Ok(Duration::hours(hours) + Duration::minutes(minutes) + Duration::seconds(seconds))
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
 
-fn duration_to_timestamp(duration: Duration, timestamp_type: TimestampType) -> String {
-    let hours = duration.num_hours();
-    let minutes = duration.num_minutes() - hours * 60;
-    let seconds = duration.num_seconds() - minutes * 60 - hours * 3600;
-
-    match timestamp_type {
-        TimestampType::MmSs => format!("{minutes:02}:{seconds:02}"),
-        TimestampType::HhMmSs => format!("{hours:02}:{minutes:02}:{seconds:02}"),
-        TimestampType::MmSsParentheses => format!("({minutes:02}:{seconds:02})"),
-        TimestampType::HhMmSsParentheses => format!("({hours:02}:{minutes:02}:{seconds:02})"),
+impl From<Vec<Chapter>> for Chapters {
+    fn from(chapters: Vec<Chapter>) -> Self {
+        Self(chapters)
     }
 }
 
-/// Reads [chapters](crate::Chapter) from MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames.
-///
-/// # Example:
-/// ```rust
-/// # use chapters::{Chapter, Link};
-/// # use pretty_assertions::assert_eq;
-/// #
-/// # fn main() {
-/// #     struct Test {
-/// #         file_path: &'static str,
-/// #         expected_chapters: Vec<Chapter>,
-/// #     }
-/// #
-/// #     let tests = vec![
-/// #         Test {
-/// #         file_path: "tests/data/id3-chapters.jfk-rice-university-speech.mp3",
-/// #         expected_chapters: vec![
-/// #             Chapter {
-/// #                 start: chrono::Duration::seconds(0),
-/// #                 title: Some(String::from("Introduction")),
-/// #                 ..Default::default()
-/// #             },
-/// #             Chapter {
-/// #                 start: chrono::Duration::seconds(9),
-/// #                 title: Some(String::from("Thanks")),
-/// #                 ..Default::default()
-/// #             },
-/// #             Chapter {
-/// #                 start: chrono::Duration::seconds(42),
-/// #                 title: Some(String::from("Status quo")),
-/// #                 ..Default::default()
-/// #             },
-/// #             Chapter {
-/// #                 start: chrono::Duration::minutes(5) + chrono::Duration::seconds(8),
-/// #                 title: Some(String::from("On being first")),
-/// #                 link: Some(Link{
-/// #                     url: url::Url::parse("https://www.osti.gov/opennet/manhattan-project-history/Events/1945/trinity.htm").unwrap(),
-/// #                     title: Some(String::from("The Trinity Test")),
-/// #                 }),
-/// #                 ..Default::default()
-/// #             },
-/// #             Chapter {
-/// #                 start: chrono::Duration::minutes(8) + chrono::Duration::seconds(8),
-/// #                 title: Some(String::from("Why we're going to the Moon")),
-/// #                 link: Some(Link{
-/// #                     url: url::Url::parse("https://www.nasa.gov/mission_pages/apollo/missions/apollo11.html").unwrap(),
-/// #                     title: None,
-/// #                 }),
-/// #                 ..Default::default()
-/// #             },
-/// #             Chapter {
-/// #                 start: chrono::Duration::minutes(16) + chrono::Duration::seconds(24),
-/// #                 title: Some(String::from("Conclusion")),
-/// #                 ..Default::default()
-/// #             },
-/// #         ],
-/// #     },
-/// #         Test {
-/// #             file_path: "tests/data/id3-chapters.jfk-rice-university-speech.no-frames.mp3",
-/// #             expected_chapters: vec![],
-/// #         },
-/// #     ];
-/// #
-/// #     for test in tests {
-/// #         let path = std::path::Path::new(test.file_path);
-/// let chapters = chapters::from_mp3_file(path).expect("Failed to parse chapters");
-/// #
-/// #        assert_eq!(chapters, test.expected_chapters);
-/// #     }
-/// # }
-pub fn from_mp3_file<P: AsRef<Path>>(path: P) -> Result<Vec<Chapter>, String> {
-    let tag = Tag::read_from_path(&path).map_err(|e| {
-        format!(
-            "Error reading ID3 tag from `{}`: {}",
-            path.as_ref().display(),
-            e
-        )
-    })?;
-    let mut chapters = Vec::new();
+impl From<Chapters> for Vec<Chapter> {
+    fn from(chapters: Chapters) -> Self {
+        chapters.0
+    }
+}
 
-    for id3_chapter in tag.chapters() {
-        let start = Duration::milliseconds(id3_chapter.start_time as i64);
+impl FromIterator<Chapter> for Chapters {
+    fn from_iter<I: IntoIterator<Item = Chapter>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
 
-        let temp_end = Duration::milliseconds(id3_chapter.end_time as i64);
-        // Some programs might encode chapters as instants, i.e., with the start and end time being the same.
-        let end = if temp_end == start {
-            None
-        } else {
-            Some(temp_end)
-        };
+impl IntoIterator for Chapters {
+    type Item = Chapter;
+    type IntoIter = std::vec::IntoIter<Chapter>;
 
-        let mut title = None;
-        let mut link = None;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
 
-        for subframe in &id3_chapter.frames {
-            match subframe.content() {
-                id3::Content::Text(text) => {
-                    title = Some(text.clone());
-                }
-                // TODO: Check if anyone uses this method as opposed to `ExtendedLink`.
-                id3::Content::Link(url) => {
-                    link = Some(Link {
-                        url: url::Url::parse(url).map_err(|e| e.to_string())?,
-                        title: None,
-                    });
-                }
-                id3::Content::ExtendedLink(extended_link) => {
-                    link = Some(Link {
-                        url: url::Url::parse(&extended_link.link).map_err(|e| e.to_string())?,
-                        title: match extended_link.description.trim() {
-                            "" => None,
-                            description => Some(description.to_string()),
-                        },
-                    });
-                }
-                _ => {}
-            }
-        }
+impl<'a> IntoIterator for &'a Chapters {
+    type Item = &'a Chapter;
+    type IntoIter = std::slice::Iter<'a, Chapter>;
 
-        chapters.push(Chapter {
-            title,
-            link,
-            start,
-            end,
-            ..Default::default()
-        });
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
     }
+}
 
-    // Order chapters by start time.
-    chapters.sort_by(|a, b| a.start.cmp(&b.start));
-
-    Ok(chapters)
+/// Problem with a list of [chapters](crate::Chapter) reported by [validate].
+#[derive(Debug, PartialEq, Clone)]
+pub enum ValidationIssue {
+    /// The chapter starts before the previous chapter.
+    OutOfOrder {
+        /// Index of the offending chapter.
+        index: usize,
+    },
+    /// The chapter starts before the previous chapter ends.
+    Overlap {
+        /// Index of the offending chapter.
+        index: usize,
+        /// The end time of the previous chapter.
+        previous_end: Duration,
+    },
+    /// The chapter starts before zero.
+    NegativeStart {
+        /// Index of the offending chapter.
+        index: usize,
+    },
+    /// The chapter ends before it starts.
+    EndBeforeStart {
+        /// Index of the offending chapter.
+        index: usize,
+    },
 }
 
-/// Writes [chapters](crate::Chapter) to MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames.
+/// Checks that [chapters](crate::Chapter) are ordered and do not overlap.
 ///
-/// If the file already has chapters, they will be replaced.
+/// The chapters are not modified; every issue found is reported together with the index of the
+/// offending chapter.
 ///
 /// # Example:
 /// ```rust
-/// # use chapters::{Chapter, Link};
+/// # use chapters::{Chapter, ValidationIssue};
 /// # use chrono::Duration;
 /// # use pretty_assertions::assert_eq;
 /// #
 /// # fn main() {
-/// #     let dst_filepath_str = "tests/data/id3-chapters.jfk-rice-university-speech.frames-added.mp3";
-/// #     let dst_filepath = std::path::Path::new(&dst_filepath_str);
-/// #
-/// #     struct Test {
-/// #         src_filepath_str: &'static str,
-/// #     }
-/// #
-/// #     let tests = vec![
-/// #         Test {
-/// #             src_filepath_str: "tests/data/id3-chapters.jfk-rice-university-speech.mp3",
-/// #         },
-/// #         Test {
-/// #             src_filepath_str: "tests/data/id3-chapters.jfk-rice-university-speech.no-frames.mp3",
-/// #         },
-/// #     ];
-/// #
-/// #     for test in tests {
-/// #         let src_filepath = std::path::Path::new(&test.src_filepath_str);
 /// let chapters = vec![
 ///     Chapter {
-///         start: Duration::seconds(0),
-///         title: Some("Introduction".to_string()),
-///         link: Some(Link{
-///             url: url::Url::parse("https://www.rice.edu").unwrap(),
-///             title: None,
-///         }),
+///         start: Duration::zero(),
+///         end: Some(Duration::seconds(40)),
+///         title: Some("Intro".to_string()),
 ///         ..Default::default()
 ///     },
 ///     Chapter {
-///         start: Duration::seconds(42),
-///         title: Some("Status quo".to_string()),
+///         start: Duration::seconds(30),
+///         end: Some(Duration::seconds(20)),
+///         title: Some("Overlapping".to_string()),
 ///         ..Default::default()
 ///     },
 ///     Chapter {
-///         start: chrono::Duration::minutes(5) + chrono::Duration::seconds(8),
-///         title: Some(String::from("On being first")),
-///         link: Some(Link{
-///             url: url::Url::parse("https://www.osti.gov/opennet/manhattan-project-history/Events/1945/trinity.htm").unwrap(),
-///             title: Some(String::from("The Trinity Test")),
-///         }),
+///         start: Duration::seconds(10),
+///         title: Some("Out of order".to_string()),
 ///         ..Default::default()
 ///     },
 /// ];
 ///
-/// chapters::to_mp3_file(src_filepath, dst_filepath, &chapters).expect("Failed to write chapters");
-/// #
-/// #         let chapters_read = chapters::from_mp3_file(dst_filepath).expect("Failed to read chapters");
-///           # assert_eq!(chapters, chapters_read);
-/// #
-/// #         // Cleanup
-/// #         std::fs::remove_file(dst_filepath).unwrap();
-/// #     }
+/// assert_eq!(
+///     chapters::validate(&chapters),
+///     Err(vec![
+///         ValidationIssue::Overlap {
+///             index: 1,
+///             previous_end: Duration::seconds(40),
+///         },
+///         ValidationIssue::EndBeforeStart { index: 1 },
+///         ValidationIssue::OutOfOrder { index: 2 },
+///     ])
+/// );
 /// # }
 /// ```
-pub fn to_mp3_file<P: AsRef<Path>>(
-    src_path: P,
-    dst_path: P,
-    chapters: &[Chapter],
-) -> Result<(), String> {
-    std::fs::copy(&src_path, &dst_path).map_err(|e| {
-        format!(
-            "Error copying `{}` to `{}`: {}",
-            src_path.as_ref().display(),
-            dst_path.as_ref().display(),
-            e
-        )
-    })?;
+pub fn validate(chapters: &[Chapter]) -> Result<(), Vec<ValidationIssue>> {
+    let mut issues = Vec::new();
 
-    let mut tag = match Tag::read_from_path(&src_path) {
-        Ok(mut tag) => {
-            tag.remove_all_chapters();
-            tag
+    for (index, chapter) in chapters.iter().enumerate() {
+        if chapter.start < Duration::zero() {
+            issues.push(ValidationIssue::NegativeStart { index });
         }
-        Err(Error {
-            kind: ErrorKind::NoTag,
-            ..
-        }) => Tag::new(),
-        Err(err) => {
-            return Err(format!(
-                "Error reading ID3 tag from `{}`: {}",
-                src_path.as_ref().display(),
-                err
-            ))
+
+        if index > 0 {
+            let previous = &chapters[index - 1];
+            if chapter.start < previous.start {
+                issues.push(ValidationIssue::OutOfOrder { index });
+            } else if let Some(previous_end) = previous.end {
+                if chapter.start < previous_end {
+                    issues.push(ValidationIssue::Overlap {
+                        index,
+                        previous_end,
+                    });
+                }
+            }
         }
-    };
 
-    for (i, chapter) in chapters.iter().enumerate() {
-        let mut id3_chapter = id3::frame::Chapter {
-            element_id: format!("chp{}", i + 1),
-            start_time: chapter.start.num_milliseconds() as u32,
-            end_time: if let Some(end) = chapter.end {
-                end.num_milliseconds() as u32
-            } else {
-                chapter.start.num_milliseconds() as u32
+        if let Some(end) = chapter.end {
+            if end < chapter.start {
+                issues.push(ValidationIssue::EndBeforeStart { index });
+            }
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// Sorts [chapters](crate::Chapter) by their start time and fills in missing end times.
+///
+/// The sort is stable, so chapters with the same start time keep their relative order. Every
+/// chapter without an end time then ends where the next chapter starts; the last chapter's end
+/// time is left untouched. Titles and all other fields are left intact.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter {
+///         start: Duration::seconds(30),
+///         title: Some("Second".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("First".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(45),
+///         title: Some("Third".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::normalize(&mut chapters);
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::seconds(30)),
+///             title: Some("First".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(30),
+///             end: Some(Duration::seconds(45)),
+///             title: Some("Second".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(45),
+///             end: None,
+///             title: Some("Third".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn normalize(chapters: &mut [Chapter]) {
+    chapters.sort_by_key(|chapter| chapter.start);
+
+    for i in 1..chapters.len() {
+        let next_start = chapters[i].start;
+        let chapter = &mut chapters[i - 1];
+        if chapter.end.is_none() {
+            chapter.end = Some(next_start);
+        }
+    }
+}
+
+/// Removes [chapters](crate::Chapter) that start at the same time as an earlier chapter.
+///
+/// The first chapter with a given start is kept, and the order of the remaining chapters is
+/// preserved. If the kept chapter has no title, link, or image, it takes them from the first
+/// removed duplicate that has one, so that no title is lost when merging lists from several
+/// sources. Returns the number of removed chapters.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(30),
+///         title: Some("Second".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("First".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(30),
+///         title: Some("Second, again".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// assert_eq!(chapters::dedupe_by_start(&mut chapters), 2);
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("First".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(30),
+///             title: Some("Second".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn dedupe_by_start(chapters: &mut Vec<Chapter>) -> usize {
+    let len = chapters.len();
+
+    let mut kept: Vec<Chapter> = Vec::with_capacity(len);
+    let mut indices = std::collections::HashMap::new();
+    for chapter in chapters.drain(..) {
+        match indices.get(&chapter.start) {
+            Some(&i) => {
+                let original: &mut Chapter = &mut kept[i];
+                original.title = original.title.take().or(chapter.title);
+                original.link = original.link.take().or(chapter.link);
+                original.image = original.image.take().or(chapter.image);
+            }
+            None => {
+                indices.insert(chapter.start, kept.len());
+                kept.push(chapter);
+            }
+        }
+    }
+
+    *chapters = kept;
+    len - chapters.len()
+}
+
+/// Merges consecutive [chapters](crate::Chapter) that have the same title, e.g., a long segment
+/// that a transcript-based tool split into several chapters.
+///
+/// Titles are compared after trimming and ignoring case; chapters without a title are never
+/// merged. A merged chapter keeps the first chapter's start and title and the last chapter's end,
+/// as well as the first [link](crate::Chapter::link) and [image](crate::Chapter::image) that any
+/// of the merged chapters has.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter {
+///         end: Some(Duration::seconds(30)),
+///         ..Chapter::at_seconds(0.0, "Monologue")
+///     },
+///     Chapter {
+///         end: Some(Duration::seconds(60)),
+///         ..Chapter::at_seconds(30.0, " monologue ")
+///     },
+///     Chapter::at_seconds(60.0, "Interview"),
+///     Chapter::at_seconds(90.0, "Monologue"),
+/// ];
+///
+/// chapters::coalesce_same_title(&mut chapters);
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             end: Some(Duration::seconds(60)),
+///             ..Chapter::at_seconds(0.0, "Monologue")
+///         },
+///         Chapter::at_seconds(60.0, "Interview"),
+///         Chapter::at_seconds(90.0, "Monologue"),
+///     ]
+/// );
+/// # }
+/// ```
+pub fn coalesce_same_title(chapters: &mut Vec<Chapter>) {
+    let same_title = |a: &Chapter, b: &Chapter| match (&a.title, &b.title) {
+        (Some(a), Some(b)) => a.trim().to_lowercase() == b.trim().to_lowercase(),
+        _ => false,
+    };
+
+    let mut coalesced: Vec<Chapter> = Vec::with_capacity(chapters.len());
+    for chapter in chapters.drain(..) {
+        match coalesced.last_mut() {
+            Some(previous) if same_title(previous, &chapter) => {
+                previous.end = chapter.end;
+                previous.link = previous.link.take().or(chapter.link);
+                previous.image = previous.image.take().or(chapter.image);
+            }
+            _ => coalesced.push(chapter),
+        }
+    }
+
+    *chapters = coalesced;
+}
+
+/// Cleans up the titles of [chapters](crate::Chapter).
+///
+/// Each title is trimmed, runs of whitespace (including tabs and newlines) are collapsed to a
+/// single space, and other ASCII control characters are removed. Titles that end up empty are
+/// set to [None].
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter::at_seconds(0.0, "  Intro\u{7}  "),
+///     Chapter::at_seconds(30.0, "Baboons\tand\n\nSteve   Jobs"),
+///     Chapter::at_seconds(60.0, " \r\n "),
+/// ];
+///
+/// chapters::sanitize_titles(&mut chapters);
+///
+/// assert_eq!(chapters[0].title, Some("Intro".to_string()));
+/// assert_eq!(chapters[1].title, Some("Baboons and Steve Jobs".to_string()));
+/// assert_eq!(chapters[2].title, None);
+/// # }
+/// ```
+pub fn sanitize_titles(chapters: &mut [Chapter]) {
+    for chapter in chapters {
+        chapter.title = chapter.title.take().and_then(|title| {
+            let title: String = title
+                .chars()
+                .filter(|c| c.is_whitespace() || !c.is_ascii_control())
+                .collect();
+            let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+            if title.is_empty() {
+                None
+            } else {
+                Some(title)
+            }
+        });
+    }
+}
+
+/// Splits the [chapter](crate::Chapter) containing `time` in two.
+///
+/// The chapter's end is set to `time`, and a new visible chapter titled `new_title`, without an
+/// image or a link, is inserted after it, starting at `time` and ending where the original chapter
+/// ended. A chapter without an end time is assumed to last until the start of the next one, and
+/// the last chapter, if it has no end time, until the end of the episode. Returns an error if
+/// `time` is not within any chapter or falls exactly on the start or end of one.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(1),
+///         end: Some(Duration::minutes(10)),
+///         title: Some("News".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::split_at(&mut chapters, Duration::minutes(4), Some("Weather".to_string()))
+///     .expect("Failed to split chapter");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(1),
+///             end: Some(Duration::minutes(4)),
+///             title: Some("News".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(4),
+///             end: Some(Duration::minutes(10)),
+///             title: Some("Weather".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+///
+/// assert!(chapters::split_at(&mut chapters, Duration::minutes(1), None).is_err());
+/// assert!(chapters::split_at(&mut chapters, Duration::minutes(11), None).is_err());
+/// # }
+/// ```
+pub fn split_at(
+    chapters: &mut Vec<Chapter>,
+    time: Duration,
+    new_title: Option<String>,
+) -> Result<(), String> {
+    for i in 0..chapters.len() {
+        let start = chapters[i].start;
+        let end = chapters[i].implied_end(chapters.get(i + 1), None);
+
+        if time == start || end == Some(time) {
+            return Err(format!("Time `{}` is on a chapter boundary", time));
+        }
+        if start < time && end.is_none_or(|end| time < end) {
+            let new_chapter = Chapter {
+                start: time,
+                end: chapters[i].end,
+                title: new_title,
+                ..Default::default()
+            };
+            chapters[i].end = Some(time);
+            chapters.insert(i + 1, new_chapter);
+            return Ok(());
+        }
+    }
+
+    Err(format!("Time `{}` is not within any chapter", time))
+}
+
+/// Makes [chapters](crate::Chapter) cover the whole episode without gaps.
+///
+/// The chapters are sorted by start time, and each chapter's end is extended to the start of the
+/// next chapter, or to `total` (the length of the episode) for the last chapter. End times that
+/// are already later are kept. If the first chapter doesn't start at zero, a chapter titled
+/// `gap_title` is inserted to cover the time before it.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter {
+///         start: Duration::minutes(5),
+///         end: Some(Duration::minutes(8)),
+///         title: Some("Baboons".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(10),
+///         title: Some("Steve Jobs".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::fill_gaps(&mut chapters, Duration::minutes(30), Some("Intro".to_string()));
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::minutes(5)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5),
+///             end: Some(Duration::minutes(10)),
+///             title: Some("Baboons".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(10),
+///             end: Some(Duration::minutes(30)),
+///             title: Some("Steve Jobs".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn fill_gaps(chapters: &mut Vec<Chapter>, total: Duration, gap_title: Option<String>) {
+    chapters.sort();
+
+    let first_start = chapters.first().map_or(total, |chapter| chapter.start);
+    if first_start > Duration::zero() {
+        chapters.insert(
+            0,
+            Chapter {
+                start: Duration::zero(),
+                end: Some(first_start),
+                title: gap_title,
+                ..Default::default()
             },
-            start_offset: 0,
-            end_offset: 0,
-            frames: Vec::new(),
+        );
+    }
+
+    for i in 0..chapters.len() {
+        let next_start = chapters.get(i + 1).map_or(total, |next| next.start);
+        if chapters[i].end.is_none_or(|end| end < next_start) {
+            chapters[i].end = Some(next_start);
+        }
+    }
+}
+
+/// Trims [chapters](crate::Chapter) to an episode of length `total`, e.g., when they were
+/// generated from a transcript that runs past the end of the audio.
+///
+/// Chapters starting at or after `total` are removed, so a chapter starting exactly at `total` is
+/// dropped. Ends after `total` are clamped to `total`; other fields and the order of the chapters
+/// are left as they are.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let mut chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(1),
+///         end: Some(Duration::minutes(3)),
+///         title: Some("Interview".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(2),
+///         title: Some("Outro".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::clip(&mut chapters, Duration::minutes(2));
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(1),
+///             end: Some(Duration::minutes(2)),
+///             title: Some("Interview".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn clip(chapters: &mut Vec<Chapter>, total: Duration) {
+    chapters.retain(|chapter| chapter.start < total);
+
+    for chapter in chapters.iter_mut() {
+        if chapter.end.is_some_and(|end| end > total) {
+            chapter.end = Some(total);
+        }
+    }
+}
+
+/// Finds the [chapter](crate::Chapter) that is active at `time`, e.g., to highlight it in a
+/// "now playing" view, and returns its index.
+///
+/// Each chapter covers the time from its start until its end time or, if it has none, the start
+/// of the following chapter (see [Chapter::contains]). At a boundary between two chapters, or
+/// where chapters overlap, the later chapter is returned. [Hidden](crate::Chapter::hidden)
+/// chapters are returned like any other; filter them out beforehand if they should be skipped.
+/// The chapters are expected to be sorted by start time.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::seconds(5),
+///         end: Some(Duration::seconds(20)),
+///         ..Default::default()
+///     },
+///     Chapter::at_seconds(20.0, "Baboons"),
+///     Chapter::at_seconds(40.0, "Steve Jobs"),
+/// ];
+///
+/// assert_eq!(chapters::active_chapter(&chapters, Duration::zero()), None);
+/// assert_eq!(chapters::active_chapter(&chapters, Duration::seconds(10)), Some(0));
+/// assert_eq!(chapters::active_chapter(&chapters, Duration::seconds(20)), Some(1));
+/// assert_eq!(chapters::active_chapter(&chapters, Duration::hours(1)), Some(2));
+/// # }
+/// ```
+pub fn active_chapter(chapters: &[Chapter], time: Duration) -> Option<usize> {
+    chapters.iter().enumerate().rposition(|(i, chapter)| {
+        chapter.contains(time, chapters.get(i + 1).map(|next| next.start))
+    })
+}
+
+/// Finds the longest and the shortest [chapters](crate::Chapter).
+///
+/// Returns the indices of the longest and the shortest chapter (in that order). The length of each
+/// chapter is computed using [implied_end](crate::Chapter::implied_end), with `total` being the
+/// length of the episode; chapters whose end cannot be determined are not considered. If several
+/// chapters are equally long, the first one is picked. Returns `None` if no chapter has a known
+/// length.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(5),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(10),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// assert_eq!(
+///     chapters::duration_extremes(&chapters, Some(Duration::minutes(12))),
+///     Some((1, 0))
+/// );
+/// assert_eq!(chapters::duration_extremes(&[], None), None);
+/// # }
+/// ```
+pub fn duration_extremes(chapters: &[Chapter], total: Option<Duration>) -> Option<(usize, usize)> {
+    let lengths = chapters.iter().enumerate().filter_map(|(i, chapter)| {
+        chapter
+            .implied_end(chapters.get(i + 1), total)
+            .map(|end| (i, end - chapter.start))
+    });
+
+    let mut extremes: Option<((usize, Duration), (usize, Duration))> = None;
+    for (i, length) in lengths {
+        extremes = Some(match extremes {
+            None => ((i, length), (i, length)),
+            Some((longest, shortest)) => (
+                if length > longest.1 {
+                    (i, length)
+                } else {
+                    longest
+                },
+                if length < shortest.1 {
+                    (i, length)
+                } else {
+                    shortest
+                },
+            ),
+        });
+    }
+
+    extremes.map(|(longest, shortest)| (longest.0, shortest.0))
+}
+
+/// Checks that no [chapter](crate::Chapter) is longer than `max_gap`.
+///
+/// This is an editorial check (e.g., so that listeners can always skip ahead within a few minutes)
+/// rather than a structural one like [validate]. The length of each chapter is computed using
+/// [implied_end](crate::Chapter::implied_end), with `total` being the length of the episode;
+/// chapters whose end cannot be determined are not checked. On failure, the indices of the
+/// chapters that are too long are returned.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(10),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(30),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// assert_eq!(
+///     chapters::validate_max_gap(&chapters, Duration::minutes(15), None),
+///     Err(vec![1])
+/// );
+/// assert_eq!(
+///     chapters::validate_max_gap(&chapters, Duration::minutes(15), Some(Duration::minutes(50))),
+///     Err(vec![1, 2])
+/// );
+/// assert_eq!(
+///     chapters::validate_max_gap(&chapters, Duration::minutes(20), Some(Duration::minutes(50))),
+///     Ok(())
+/// );
+/// # }
+/// ```
+pub fn validate_max_gap(
+    chapters: &[Chapter],
+    max_gap: Duration,
+    total: Option<Duration>,
+) -> Result<(), Vec<usize>> {
+    let too_long: Vec<usize> = chapters
+        .iter()
+        .enumerate()
+        .filter(|(i, chapter)| {
+            chapter
+                .implied_end(chapters.get(i + 1), total)
+                .is_some_and(|end| end - chapter.start > max_gap)
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    if too_long.is_empty() {
+        Ok(())
+    } else {
+        Err(too_long)
+    }
+}
+
+/// Computes how long each [chapter](crate::Chapter) runs.
+///
+/// The length of a chapter is computed using [implied_end](crate::Chapter::implied_end): its own
+/// end time if set, otherwise the start of the next chapter, or `total` (the length of the
+/// episode) for the last chapter. The chapters don't need to be sorted; the lengths are returned
+/// in the same order as the chapters. If the last chapter has no end time and `total` is not
+/// given, an error is returned.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::minutes(10),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::zero(),
+///         end: Some(Duration::minutes(5)),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// assert_eq!(
+///     chapters::durations(&chapters, Some(Duration::minutes(30))),
+///     Ok(vec![Duration::minutes(20), Duration::minutes(5)])
+/// );
+/// assert_eq!(
+///     chapters::durations(&chapters, None),
+///     Err("Cannot determine the end of chapter 0".to_string())
+/// );
+/// # }
+/// ```
+pub fn durations(chapters: &[Chapter], total: Option<Duration>) -> Result<Vec<Duration>, String> {
+    let mut order: Vec<usize> = (0..chapters.len()).collect();
+    order.sort_by_key(|&i| chapters[i].start);
+
+    let mut durations = vec![Duration::zero(); chapters.len()];
+    for (position, &i) in order.iter().enumerate() {
+        let next = order.get(position + 1).map(|&next| &chapters[next]);
+        let end = chapters[i]
+            .implied_end(next, total)
+            .ok_or_else(|| format!("Cannot determine the end of chapter {}", i))?;
+        durations[i] = end - chapters[i].start;
+    }
+
+    Ok(durations)
+}
+
+/// Reduces the number of [chapters](crate::Chapter) to at most `target` by merging neighbouring
+/// chapters.
+///
+/// The chapters are first sorted by their start time. Then, while there are too many chapters,
+/// the shortest chapter (measured from its start to the start of the next chapter) is merged with
+/// the chapter that follows it; if several are equally short, the earliest one is merged. The
+/// merged chapter keeps the start, title, and other fields of the earlier chapter (falling back to
+/// the later chapter's title, image, and link if the earlier one has none) and the end time of
+/// the later chapter. The first chapter's start is therefore always preserved.
+///
+/// If there are already at most `target` chapters, they are returned unchanged. A non-empty list
+/// is never reduced below a single chapter.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(10),
+///         title: Some("Topic".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(11),
+///         title: Some("Topic, continued".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(20),
+///         title: Some("Outro".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let reduced = chapters::reduce_to(&chapters, 3);
+///
+/// assert_eq!(
+///     reduced,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(10),
+///             title: Some("Topic".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(20),
+///             title: Some("Outro".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn reduce_to(chapters: &[Chapter], target: usize) -> Vec<Chapter> {
+    let mut chapters = chapters.to_vec();
+    if chapters.len() <= target {
+        return chapters;
+    }
+
+    chapters.sort();
+
+    while chapters.len() > target.max(1) {
+        let shortest = (0..chapters.len() - 1)
+            .min_by_key(|&i| chapters[i + 1].start - chapters[i].start)
+            .unwrap_or(0);
+
+        let next = chapters.remove(shortest + 1);
+        let chapter = &mut chapters[shortest];
+        chapter.end = next.end;
+        if chapter.title.is_none() {
+            chapter.title = next.title;
+        }
+        if chapter.image.is_none() {
+            chapter.image = next.image;
+        }
+        if chapter.link.is_none() {
+            chapter.link = next.link;
+        }
+    }
+
+    chapters
+}
+
+/// Merges two lists of [chapters](crate::Chapter), e.g., ones read from ID3 tags and ones parsed
+/// from the episode description.
+///
+/// Chapters from `a` and `b` whose starts are within `tolerance` of each other are treated as the
+/// same marker; each chapter in `b` is matched with the closest unmatched chapter in `a`. Of two
+/// matched chapters, the one with more of `title`, `link`, and `image` set is kept (preferring `a`
+/// on ties), and any of these fields that it lacks, as well as `end`, are filled in from the other
+/// one. Unmatched chapters are kept as they are. The result is sorted by start time.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let id3_chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::milliseconds(60_200),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com").unwrap(),
+///             title: None,
+///         }),
+///         ..Default::default()
+///     },
+/// ];
+/// let description_chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(1),
+///         title: Some("News".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5),
+///         title: Some("Outro".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let merged = chapters::merge(&id3_chapters, &description_chapters, Duration::seconds(1));
+///
+/// assert_eq!(
+///     merged,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::milliseconds(60_200),
+///             title: Some("News".to_string()),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com").unwrap(),
+///                 title: None,
+///             }),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5),
+///             title: Some("Outro".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn merge(a: &[Chapter], b: &[Chapter], tolerance: Duration) -> Vec<Chapter> {
+    let completeness = |chapter: &Chapter| {
+        [
+            chapter.title.is_some(),
+            chapter.link.is_some(),
+            chapter.image.is_some(),
+        ]
+        .iter()
+        .filter(|is_set| **is_set)
+        .count()
+    };
+
+    let mut merged = a.to_vec();
+    let mut matched = vec![false; a.len()];
+
+    for chapter in b {
+        let closest = (0..a.len())
+            .filter(|&i| !matched[i] && (a[i].start - chapter.start).abs() <= tolerance)
+            .min_by_key(|&i| (a[i].start - chapter.start).abs());
+
+        let Some(i) = closest else {
+            merged.push(chapter.clone());
+            continue;
+        };
+        matched[i] = true;
+
+        let (mut kept, other) = if completeness(chapter) > completeness(&a[i]) {
+            (chapter.clone(), &a[i])
+        } else {
+            (a[i].clone(), chapter)
         };
+        kept.end = kept.end.or(other.end);
+        kept.title = kept.title.or_else(|| other.title.clone());
+        kept.link = kept.link.or_else(|| other.link.clone());
+        kept.image = kept.image.or_else(|| other.image.clone());
+        merged[i] = kept;
+    }
+
+    merged.sort();
+
+    merged
+}
+
+/// Difference between two lists of [chapters](crate::Chapter) reported by [diff].
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChapterChange {
+    /// The chapter is only in the new list.
+    Added(Chapter),
+    /// The chapter is only in the old list.
+    Removed(Chapter),
+    /// The chapter's title changed.
+    Retitled {
+        /// The old title.
+        from: Option<String>,
+        /// The new title.
+        to: Option<String>,
+    },
+    /// The chapter's start time changed.
+    Moved {
+        /// The old start time.
+        from_start: Duration,
+        /// The new start time.
+        to_start: Duration,
+    },
+}
+
+/// Compares two lists of [chapters](crate::Chapter), e.g., before and after editing.
+///
+/// A chapter in `new` is matched with the chapter in `old` whose start is nearest to its own, as
+/// long as they are at most 5 seconds apart; closer pairs are matched first. Matched chapters
+/// whose start or title differ are reported as [moved](ChapterChange::Moved) or
+/// [retitled](ChapterChange::Retitled) (or both), and unmatched chapters as
+/// [added](ChapterChange::Added) or [removed](ChapterChange::Removed). Changes are ordered by
+/// time: the new start for matched and added chapters, and the old start for removed ones.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, ChapterChange};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let old = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(1),
+///         title: Some("News".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5),
+///         title: Some("Outro".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+/// let new = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Welcome".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(1) + Duration::seconds(2),
+///         title: Some("News".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(3),
+///         title: Some("Interview".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// assert_eq!(
+///     chapters::diff(&old, &new),
+///     vec![
+///         ChapterChange::Retitled {
+///             from: Some("Intro".to_string()),
+///             to: Some("Welcome".to_string()),
+///         },
+///         ChapterChange::Moved {
+///             from_start: Duration::minutes(1),
+///             to_start: Duration::minutes(1) + Duration::seconds(2),
+///         },
+///         ChapterChange::Added(Chapter {
+///             start: Duration::minutes(3),
+///             title: Some("Interview".to_string()),
+///             ..Default::default()
+///         }),
+///         ChapterChange::Removed(Chapter {
+///             start: Duration::minutes(5),
+///             title: Some("Outro".to_string()),
+///             ..Default::default()
+///         }),
+///     ]
+/// );
+/// # }
+/// ```
+pub fn diff(old: &[Chapter], new: &[Chapter]) -> Vec<ChapterChange> {
+    let tolerance = Duration::seconds(5);
+
+    let mut pairs: Vec<(usize, usize)> = (0..old.len())
+        .flat_map(|i| (0..new.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| (old[i].start - new[j].start).abs() <= tolerance)
+        .collect();
+    pairs.sort_by_key(|&(i, j)| (old[i].start - new[j].start).abs());
 
-        if let Some(title) = &chapter.title {
-            let frame = id3::frame::Frame::with_content("TIT2", id3::Content::Text(title.clone()));
-            id3_chapter.frames.push(frame);
+    let mut old_matched = vec![false; old.len()];
+    let mut new_matched = vec![false; new.len()];
+    let mut changes = Vec::new();
+
+    for (i, j) in pairs {
+        if old_matched[i] || new_matched[j] {
+            continue;
         }
+        old_matched[i] = true;
+        new_matched[j] = true;
 
-        if let Some(link) = &chapter.link {
-            // title or "" if None
-            let link_title = link.title.as_ref().map_or("", |t| t.as_str());
-            let frame = id3::frame::Frame::with_content(
-                "WXXX",
-                id3::Content::ExtendedLink(id3::frame::ExtendedLink {
-                    link: link.url.to_string(),
-                    description: link_title.to_string(),
-                }),
-            );
-            id3_chapter.frames.push(frame);
+        if old[i].start != new[j].start {
+            changes.push((
+                new[j].start,
+                ChapterChange::Moved {
+                    from_start: old[i].start,
+                    to_start: new[j].start,
+                },
+            ));
+        }
+        if old[i].title != new[j].title {
+            changes.push((
+                new[j].start,
+                ChapterChange::Retitled {
+                    from: old[i].title.clone(),
+                    to: new[j].title.clone(),
+                },
+            ));
         }
+    }
 
-        tag.add_frame(id3::frame::Frame::with_content(
-            "CHAP",
-            id3::Content::Chapter(id3_chapter),
-        ));
+    for (chapter, _) in new.iter().zip(new_matched).filter(|(_, matched)| !matched) {
+        changes.push((chapter.start, ChapterChange::Added(chapter.clone())));
+    }
+    for (chapter, _) in old.iter().zip(old_matched).filter(|(_, matched)| !matched) {
+        changes.push((chapter.start, ChapterChange::Removed(chapter.clone())));
     }
 
-    tag.write_to_path(&dst_path, Version::Id3v24).map_err(|e| {
-        format!(
-            "Error writing ID3  tag to `{}`: {}",
-            dst_path.as_ref().display(),
-            e
-        )
-    })?;
+    changes.sort_by_key(|(time, _)| *time);
+
+    changes.into_iter().map(|(_, change)| change).collect()
+}
+
+/// Writes the locations of [chapters](crate::Chapter) as a [GeoJSON](https://geojson.org)
+/// `FeatureCollection`.
+///
+/// Every chapter whose [location](crate::Location) has a `geo` URI becomes a `Point` feature with
+/// the chapter's `title` and `start` (in seconds) as properties. Chapters without a location or
+/// without coordinates are skipped.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Location};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(90) + Duration::milliseconds(500),
+///         title: Some("Downtown Austin".to_string()),
+///         location: Some(Location {
+///             name: "Austin, TX".to_string(),
+///             geo: Some("geo:30.2672,-97.7431".to_string()),
+///             osm: Some("R113314".to_string()),
+///         }),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let geojson = chapters::to_geojson(&chapters).expect("Failed to write GeoJSON");
+///
+/// assert_eq!(geojson, r#"{
+///   "type": "FeatureCollection",
+///   "features": [
+///     {
+///       "type": "Feature",
+///       "geometry": {
+///         "type": "Point",
+///         "coordinates": [
+///           -97.7431,
+///           30.2672
+///         ]
+///       },
+///       "properties": {
+///         "title": "Downtown Austin",
+///         "start": 90.5
+///       }
+///     }
+///   ]
+/// }"#);
+/// # }
+/// ```
+#[cfg(feature = "json")]
+pub fn to_geojson(chapters: &[Chapter]) -> Result<String, String> {
+    #[derive(Serialize)]
+    struct FeatureCollection<'a> {
+        r#type: &'static str,
+        features: Vec<Feature<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct Feature<'a> {
+        r#type: &'static str,
+        geometry: Point,
+        properties: Properties<'a>,
+    }
+
+    #[derive(Serialize)]
+    struct Point {
+        r#type: &'static str,
+        // GeoJSON positions are longitude first.
+        coordinates: [f64; 2],
+    }
+
+    #[derive(Serialize)]
+    struct Properties<'a> {
+        title: &'a Option<String>,
+        #[serde(serialize_with = "serialization::duration_to_float")]
+        start: Duration,
+    }
+
+    let mut features = Vec::new();
+
+    for chapter in chapters {
+        let coordinates = match chapter.location.as_ref().and_then(|l| l.coordinates()) {
+            Some(coordinates) => coordinates?,
+            None => continue,
+        };
+        let (latitude, longitude) = coordinates;
+
+        features.push(Feature {
+            r#type: "Feature",
+            geometry: Point {
+                r#type: "Point",
+                coordinates: [longitude, latitude],
+            },
+            properties: Properties {
+                title: &chapter.title,
+                start: chapter.start,
+            },
+        });
+    }
+
+    let feature_collection = FeatureCollection {
+        r#type: "FeatureCollection",
+        features,
+    };
+
+    serde_json::to_string_pretty(&feature_collection).map_err(|e| e.to_string())
+}
+
+/// Computes a fingerprint of [chapters](crate::Chapter) for caching and change detection.
+///
+/// Only the start and end times (in milliseconds), titles, link URLs, and images of the chapters
+/// are taken into account, in order. Everything else, such as link titles or where the chapters
+/// were read from, is deliberately ignored so that cosmetic differences do not invalidate caches.
+///
+/// The fingerprint is computed with the [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+/// hash, so it is stable across program runs, platforms, and Rust versions.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// #
+/// # fn main() {
+/// let chapters = vec![Chapter {
+///     start: Duration::zero(),
+///     title: Some("Intro".to_string()),
+///     link: Some(Link {
+///         url: url::Url::parse("https://example.com").unwrap(),
+///         title: None,
+///     }),
+///     ..Default::default()
+/// }];
+///
+/// let mut retitled_link = chapters.clone();
+/// retitled_link[0].link.as_mut().unwrap().title = Some("Example".to_string());
+/// assert_eq!(chapters::fingerprint(&chapters), chapters::fingerprint(&retitled_link));
+///
+/// let mut retitled = chapters.clone();
+/// retitled[0].title = Some("Introduction".to_string());
+/// assert_ne!(chapters::fingerprint(&chapters), chapters::fingerprint(&retitled));
+/// # }
+/// ```
+pub fn fingerprint(chapters: &[Chapter]) -> u64 {
+    struct Fnv1a(u64);
+
+    impl Fnv1a {
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 ^= *byte as u64;
+                self.0 = self.0.wrapping_mul(0x100000001b3);
+            }
+        }
+
+        // Fields are length-prefixed so that, e.g., titles "ab" + "c" and "a" + "bc" differ.
+        fn write_field(&mut self, field: Option<&[u8]>) {
+            match field {
+                Some(bytes) => {
+                    self.write(&[1]);
+                    self.write(&(bytes.len() as u64).to_le_bytes());
+                    self.write(bytes);
+                }
+                None => self.write(&[0]),
+            }
+        }
+    }
+
+    let mut hasher = Fnv1a(0xcbf29ce484222325);
+
+    for chapter in chapters {
+        hasher.write_field(Some(&chapter.start.num_milliseconds().to_le_bytes()));
+        hasher.write_field(
+            chapter
+                .end
+                .map(|end| end.num_milliseconds().to_le_bytes())
+                .as_ref()
+                .map(|bytes| bytes.as_slice()),
+        );
+        hasher.write_field(chapter.title.as_ref().map(|title| title.as_bytes()));
+        hasher.write_field(
+            chapter
+                .link
+                .as_ref()
+                .map(|link| link.url.as_str().as_bytes()),
+        );
+        match &chapter.image {
+            Some(Image::Url { url, .. }) => hasher.write_field(Some(url.as_str().as_bytes())),
+            Some(Image::Data { mime_type, data }) => {
+                hasher.write_field(Some(mime_type.as_bytes()));
+                hasher.write_field(Some(data));
+            }
+            None => hasher.write_field(None),
+        }
+    }
+
+    hasher.0
+}
+
+/// Reads [chapters](crate::Chapter) from data in an unknown format, detecting the format from the
+/// content.
+///
+/// After skipping a byte order mark and leading whitespace, the data is read as
+/// 1. [JSON chapters](crate::from_json) if it starts with `{` (requires the `json` feature),
+/// 2. [WebVTT](crate::from_webvtt) if it starts with `WEBVTT`,
+/// 3. an [episode description](crate::from_description) otherwise.
+///
+/// The first successful result is returned. Input that matches none of the formats, or for which
+/// the detected parser fails, falls back to description parsing; if that finds no chapters
+/// either, the error of the detected parser is returned.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let vtt = "WEBVTT\n\n00:00.000 --> 05:04.000\nIntro\n";
+/// let description = "00:00 Intro\n05:04 Baboons";
+///
+/// assert_eq!(
+///     chapters::from_bytes(vtt.as_bytes()),
+///     Ok(vec![Chapter {
+///         start: Duration::zero(),
+///         end: Some(Duration::minutes(5) + Duration::seconds(4)),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     }])
+/// );
+/// assert_eq!(
+///     chapters::from_bytes(description.as_bytes()).map(|chapters| chapters.len()),
+///     Ok(2)
+/// );
+/// # }
+/// ```
+pub fn from_bytes(data: &[u8]) -> Result<Vec<Chapter>, String> {
+    let text = std::str::from_utf8(data).map_err(|e| format!("Input is not valid UTF-8: {}", e))?;
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    let detected = if text.starts_with("WEBVTT") {
+        Some(from_webvtt(text.as_bytes()))
+    } else {
+        None
+    };
+    #[cfg(feature = "json")]
+    let detected = if text.starts_with('{') {
+        Some(from_json(text.as_bytes()))
+    } else {
+        detected
+    };
+
+    match detected {
+        Some(Ok(chapters)) => Ok(chapters),
+        Some(Err(e)) => match from_description(text) {
+            Ok(chapters) if !chapters.is_empty() => Ok(chapters),
+            _ => Err(e),
+        },
+        None => from_description(text),
+    }
+}
+
+/// Chapter formats that can be [read](fn@read) and [written](fn@write) by name, e.g., in a converter tool.
+///
+/// Which variants exist depends on the enabled features, so matching on this enum needs a
+/// wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChapterFormat {
+    /// [JSON chapters](crate::from_json) of the Podcast namespace. Requires the `json` feature.
+    #[cfg(feature = "json")]
+    Json,
+    /// [WebVTT](crate::from_webvtt) subtitles. Can only be read.
+    WebVtt,
+    /// [Episode description](crate::from_description) (show notes).
+    Description,
+    /// [Podlove Simple Chapters](crate::from_psc).
+    Psc,
+    /// [CUE sheets](crate::from_cue). Can only be read, since writing them needs the name of the
+    /// audio file; use [to_cue] instead.
+    Cue,
+    /// [FFmetadata](crate::from_ffmetadata) files of FFmpeg.
+    FfMetadata,
+}
+
+/// Reads [chapters](crate::Chapter) in the given format.
+///
+/// This calls the format's reader, e.g., [from_json] for [ChapterFormat::Json]. See [from_bytes]
+/// to detect the format from the content instead.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, ChapterFormat};
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let description = "00:00 Intro\n05:04 Baboons";
+///
+/// let chapters = chapters::read(description.as_bytes(), ChapterFormat::Description)
+///     .expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter::at_seconds(0.0, "Intro"),
+///         Chapter::at_seconds(304.0, "Baboons"),
+///     ]
+/// );
+/// # }
+/// ```
+pub fn read(mut reader: impl std::io::Read, format: ChapterFormat) -> Result<Vec<Chapter>, String> {
+    match format {
+        #[cfg(feature = "json")]
+        ChapterFormat::Json => from_json(reader),
+        ChapterFormat::WebVtt => from_webvtt(reader),
+        ChapterFormat::Description => {
+            let mut description = String::new();
+            reader
+                .read_to_string(&mut description)
+                .map_err(|e| format!("Error reading description: {}", e))?;
+            from_description(&description)
+        }
+        ChapterFormat::Psc => from_psc(reader),
+        ChapterFormat::Cue => from_cue(reader),
+        ChapterFormat::FfMetadata => from_ffmetadata(reader),
+    }
+}
 
-    Ok(())
+/// Writes [chapters](crate::Chapter) in the given format.
+///
+/// This calls the format's writer, e.g., [to_json] for [ChapterFormat::Json]. Returns an error for
+/// formats that can only be read, i.e., [WebVTT](ChapterFormat::WebVtt) and
+/// [CUE sheets](ChapterFormat::Cue).
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, ChapterFormat};
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter::at_seconds(0.0, "Intro"),
+///     Chapter::at_seconds(304.0, "Baboons"),
+/// ];
+///
+/// assert_eq!(
+///     chapters::write(&chapters, ChapterFormat::Description),
+///     Ok("00:00 Intro\n05:04 Baboons\n".to_string())
+/// );
+/// assert_eq!(
+///     chapters::write(&chapters, ChapterFormat::WebVtt),
+///     Err("Writing WebVTT is not supported".to_string())
+/// );
+/// # }
+/// ```
+pub fn write(chapters: &[Chapter], format: ChapterFormat) -> Result<String, String> {
+    match format {
+        #[cfg(feature = "json")]
+        ChapterFormat::Json => to_json(chapters),
+        ChapterFormat::WebVtt => Err(String::from("Writing WebVTT is not supported")),
+        ChapterFormat::Description => to_description(chapters),
+        ChapterFormat::Psc => to_psc(chapters),
+        ChapterFormat::Cue => Err(String::from(
+            "Writing CUE sheets needs the name of the audio file; use `to_cue` instead",
+        )),
+        ChapterFormat::FfMetadata => to_ffmetadata(chapters),
+    }
 }
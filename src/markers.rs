@@ -0,0 +1,175 @@
+use crate::Chapter;
+use chrono::Duration;
+
+/// Reads [chapters](crate::Chapter) from a tab-separated marker export of a video or audio editor
+/// (e.g., Descript or Adobe Premiere).
+///
+/// Each line has a marker name, a start timecode, and a duration timecode. If the first line is a
+/// header (e.g., with columns "Marker Name", "Description", "In", "Out", and "Duration"), the
+/// columns are located by their names ("Name", "In" or "Start", and "Duration"); otherwise the
+/// first three columns are used in that order.
+///
+/// Timecodes are in `HH:MM:SS:FF` format and are converted using the frame rate `fps`. Drop-frame
+/// timecodes, which separate frames with a semicolon (`HH:MM:SS;FF`), are supported for the frame
+/// rates 29.97 and 59.94. The marker name becomes the chapter title, and a non-zero duration
+/// sets the chapter's end.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let tsv = "Marker Name\tDescription\tIn\tOut\tDuration\tMarker Type
+/// Intro\t\t00:00:00:00\t00:00:30:00\t00:00:30:00\tComment
+/// Baboons\t\t00:00:30:12\t00:00:30:12\t00:00:00:00\tChapter
+/// ";
+///
+/// let chapters = chapters::from_marker_tsv(tsv.as_bytes(), 25.0).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::seconds(30)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(30) + Duration::milliseconds(480),
+///             title: Some("Baboons".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+///
+/// // Drop-frame timecode at 29.97 fps: frame 00:10:00;00 is exactly 600 seconds.
+/// let tsv = "Segment\t00:10:00;00\t00:00:00;00\n";
+/// let chapters = chapters::from_marker_tsv(tsv.as_bytes(), 29.97).expect("Failed to parse chapters");
+/// assert_eq!(chapters[0].start, Duration::minutes(10));
+/// # }
+/// ```
+pub fn from_marker_tsv<R: std::io::Read>(mut reader: R, fps: f64) -> Result<Vec<Chapter>, String> {
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err(format!("Invalid frame rate {}", fps));
+    }
+
+    let mut tsv = String::new();
+    reader
+        .read_to_string(&mut tsv)
+        .map_err(|e| format!("Error reading markers: {}", e))?;
+
+    let mut lines = tsv
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+
+    // Column indices of the name, the start, and the duration.
+    let mut columns = (0, 1, 2);
+    if let Some(header) = lines.peek() {
+        let header: Vec<String> = header
+            .split('\t')
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+        let find = |names: &[&str]| header.iter().position(|c| names.contains(&c.as_str()));
+        if let (Some(name), Some(start), Some(duration)) = (
+            find(&["name", "marker name"]),
+            find(&["in", "start"]),
+            find(&["duration"]),
+        ) {
+            columns = (name, start, duration);
+            lines.next();
+        }
+    }
+
+    let mut chapters = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split('\t').map(|field| field.trim()).collect();
+        let field = |index: usize| {
+            fields
+                .get(index)
+                .copied()
+                .ok_or_else(|| format!("Marker line `{}` has too few columns", line))
+        };
+
+        let title = field(columns.0)?;
+        let start = parse_timecode(field(columns.1)?, fps)?;
+        let duration = parse_timecode(field(columns.2)?, fps)?;
+
+        chapters.push(Chapter {
+            start,
+            end: if duration > Duration::zero() {
+                Some(
+                    start
+                        .checked_add(&duration)
+                        .ok_or_else(|| format!("Marker line `{}` ends out of range", line))?,
+                )
+            } else {
+                None
+            },
+            title: if title.is_empty() {
+                None
+            } else {
+                Some(title.to_string())
+            },
+            ..Default::default()
+        });
+    }
+
+    Ok(chapters)
+}
+
+/// Parses an `HH:MM:SS:FF` (or drop-frame `HH:MM:SS;FF`) timecode into a duration.
+fn parse_timecode(s: &str, fps: f64) -> Result<Duration, String> {
+    let invalid = || format!("Invalid timecode `{}`", s);
+
+    // Drop-frame timecode only exists for the NTSC rates of 29.97 and 59.94 fps.
+    let drop_frame = s.contains(';');
+    if drop_frame && [29.97, 59.94].iter().all(|ntsc| (fps - ntsc).abs() > 0.01) {
+        return Err(invalid());
+    }
+    let parts = s
+        .split([':', ';'])
+        .map(|part| {
+            if !part.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            part.parse::<i64>().map_err(|_| invalid())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Timecodes count frames at the nominal (integer) rate, e.g., 30 for 29.97 fps.
+    let nominal_fps = fps.round() as i64;
+    let (hours, minutes, seconds, frames) = match parts[..] {
+        [hours, minutes, seconds, frames]
+            if minutes < 60 && seconds < 60 && frames < nominal_fps.max(1) =>
+        {
+            (hours, minutes, seconds, frames)
+        }
+        _ => return Err(invalid()),
+    };
+    let frame_number = || -> Option<i64> {
+        let total_minutes = hours.checked_mul(60)?.checked_add(minutes)?;
+        let total_seconds = total_minutes.checked_mul(60)?.checked_add(seconds)?;
+        let mut frame_number = total_seconds
+            .checked_mul(nominal_fps)?
+            .checked_add(frames)?;
+        if drop_frame {
+            // Drop-frame timecode skips 2 frame numbers (4 at 59.94 fps) at the start of every
+            // minute, except for every tenth minute.
+            let dropped_per_minute = (nominal_fps as f64 / 15.0).round() as i64;
+            frame_number = frame_number
+                .checked_sub(dropped_per_minute.checked_mul(total_minutes - total_minutes / 10)?)?;
+        }
+        Some(frame_number)
+    };
+    let frame_number = frame_number().ok_or_else(invalid)?;
+
+    let milliseconds = (frame_number as f64 * 1000.0 / fps).round();
+    if !(0.0..i64::MAX as f64).contains(&milliseconds) {
+        return Err(invalid());
+    }
+    Duration::try_milliseconds(milliseconds as i64).ok_or_else(invalid)
+}
@@ -0,0 +1,939 @@
+use crate::{Chapter, Image, Link};
+use chrono::Duration;
+use id3::{Error, ErrorKind, Tag, TagLike, Version};
+use std::path::Path;
+
+/// Reads [chapters](crate::Chapter) from MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames.
+///
+/// The first picture (`APIC`) frame embedded in a chapter is used as that chapter's image, and any
+/// further ones as its [additional images](crate::Chapter::images); if a picture's MIME type
+/// is "-->", the picture data is read as the image URL. Failing that, a
+/// top-level picture frame whose description is equal to the chapter's element ID (e.g., "chp1")
+/// is used. If the tag has a table of contents (`CTOC` frame),
+/// chapters that are not listed in it are marked as [hidden](crate::Chapter::hidden).
+/// The element ID of each `CHAP` frame is kept as the chapter's
+/// [element ID](crate::Chapter::element_id), and a `TLAN` frame in a chapter is read as its
+/// [language](crate::Chapter::language). Chapters in ID3v2.2 tags, which have no standard chapter frame, are read from `CHP` frames laid
+/// out like ID3v2.3 `CHAP` frames.
+/// Chapters whose end time is equal to their start time or is `0xFFFFFFFF` have no
+/// [end](crate::Chapter::end); see [from_mp3_file_with_options] to keep the former.
+///
+/// If there is no ID3v2 tag at the start of the file, e.g., because an encoder put some garbage
+/// before it or appended it to the end, the first and last MiB of the file are searched for one.
+/// If no tag is found, no chapters are returned.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Image, Link};
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// #     struct Test {
+/// #         file_path: &'static str,
+/// #         expected_chapters: Vec<Chapter>,
+/// #     }
+/// #
+/// #     let tests = vec![
+/// #         Test {
+/// #         file_path: "tests/data/id3-chapters.jfk-rice-university-speech.mp3",
+/// #         expected_chapters: vec![
+/// #             Chapter {
+/// #                 start: chrono::Duration::seconds(0),
+/// #                 title: Some(String::from("Introduction")),
+/// #                 element_id: Some(String::from("chp03")),
+/// #                 ..Default::default()
+/// #             },
+/// #             Chapter {
+/// #                 start: chrono::Duration::seconds(9),
+/// #                 title: Some(String::from("Thanks")),
+/// #                 element_id: Some(String::from("chp01")),
+/// #                 ..Default::default()
+/// #             },
+/// #             Chapter {
+/// #                 start: chrono::Duration::seconds(42),
+/// #                 title: Some(String::from("Status quo")),
+/// #                 element_id: Some(String::from("chp02")),
+/// #                 ..Default::default()
+/// #             },
+/// #             Chapter {
+/// #                 start: chrono::Duration::minutes(5) + chrono::Duration::seconds(8),
+/// #                 title: Some(String::from("On being first")),
+/// #                 element_id: Some(String::from("chp04")),
+/// #                 link: Some(Link{
+/// #                     url: url::Url::parse("https://www.osti.gov/opennet/manhattan-project-history/Events/1945/trinity.htm").unwrap(),
+/// #                     title: Some(String::from("The Trinity Test")),
+/// #                 }),
+/// #                 image: Some(Image::Data {
+/// #                     mime_type: String::from("image/png"),
+/// #                     data: include_bytes!("../tests/data/id3-chapters.jfk-rice-university-speech.on-being-first.jpg").to_vec(),
+/// #                 }),
+/// #                 ..Default::default()
+/// #             },
+/// #             Chapter {
+/// #                 start: chrono::Duration::minutes(8) + chrono::Duration::seconds(8),
+/// #                 title: Some(String::from("Why we're going to the Moon")),
+/// #                 element_id: Some(String::from("chp05")),
+/// #                 link: Some(Link{
+/// #                     url: url::Url::parse("https://www.nasa.gov/mission_pages/apollo/missions/apollo11.html").unwrap(),
+/// #                     title: None,
+/// #                 }),
+/// #                 ..Default::default()
+/// #             },
+/// #             Chapter {
+/// #                 start: chrono::Duration::minutes(16) + chrono::Duration::seconds(24),
+/// #                 title: Some(String::from("Conclusion")),
+/// #                 element_id: Some(String::from("chp06")),
+/// #                 ..Default::default()
+/// #             },
+/// #         ],
+/// #     },
+/// #         Test {
+/// #             file_path: "tests/data/id3-chapters.jfk-rice-university-speech.no-frames.mp3",
+/// #             expected_chapters: vec![],
+/// #         },
+/// #     ];
+/// #
+/// #     for test in tests {
+/// #         let path = std::path::Path::new(test.file_path);
+/// let chapters = chapters::from_mp3_file(path).expect("Failed to parse chapters");
+/// #
+/// #        assert_eq!(chapters, test.expected_chapters);
+/// #     }
+/// # }
+pub fn from_mp3_file<P: AsRef<Path>>(path: P) -> Result<Vec<Chapter>, String> {
+    from_mp3_file_with_options(path, &Mp3Options::default())
+}
+
+/// Options for [from_mp3_file_with_options].
+#[derive(Debug, Clone)]
+pub struct Mp3Options {
+    /// Read chapters whose end time is equal to their start time as instants, i.e., with no
+    /// [end](crate::Chapter::end). Some encoders write chapters this way when the end is unknown,
+    /// while others use it on purpose for zero-length markers. Enabled by default; if disabled,
+    /// the end time is kept as is.
+    pub instants_have_no_end: bool,
+}
+
+impl Default for Mp3Options {
+    fn default() -> Self {
+        Self {
+            instants_have_no_end: true,
+        }
+    }
+}
+
+/// Reads [chapters](crate::Chapter) from MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames using the given options.
+///
+/// [from_mp3_file] is equivalent to calling this function with the default options. Regardless of
+/// the options, an end time of `0xFFFFFFFF` milliseconds, which some encoders use as an "unknown"
+/// sentinel, is read as no [end](crate::Chapter::end).
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Mp3Options};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let src_path = "tests/data/id3-chapters.jfk-rice-university-speech.mp3";
+/// let dst_path = std::env::temp_dir().join("chapters-doctest-mp3-options.mp3");
+///
+/// // Chapters without an end are written as instants.
+/// let chapters = vec![Chapter {
+///     start: Duration::seconds(9),
+///     title: Some("Thanks".to_string()),
+///     ..Default::default()
+/// }];
+/// chapters::to_mp3_file(src_path.as_ref(), dst_path.as_path(), &chapters).unwrap();
+///
+/// let chapters = chapters::from_mp3_file(&dst_path).unwrap();
+/// assert_eq!(chapters[0].end, None);
+///
+/// let options = Mp3Options {
+///     instants_have_no_end: false,
+/// };
+/// let chapters = chapters::from_mp3_file_with_options(&dst_path, &options).unwrap();
+/// assert_eq!(chapters[0].end, Some(Duration::seconds(9)));
+/// # std::fs::remove_file(&dst_path).unwrap();
+/// # }
+/// ```
+pub fn from_mp3_file_with_options<P: AsRef<Path>>(
+    path: P,
+    options: &Mp3Options,
+) -> Result<Vec<Chapter>, String> {
+    match read_tag(path.as_ref())? {
+        Some(tag) => chapters_from_tag(&tag, options),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Number of bytes at the start and at the end of an MP3 file that are searched for an ID3 tag
+/// if there is none at the very start.
+const ID3_SCAN_LIMIT: u64 = 1024 * 1024;
+
+/// Element ID of the written `CTOC` frame, which chapters can't use for their `CHAP` frames.
+const TOC_ELEMENT_ID: &str = "toc";
+
+/// Reads the ID3 tag of the file, returning `None` if it has none.
+///
+/// Some encoders put garbage before the tag or append the tag to the end of the file, so if there
+/// is no tag at the start, the first and last [ID3_SCAN_LIMIT] bytes are searched for an ID3v2
+/// header and the first one that can be decoded is used.
+fn read_tag(path: &Path) -> Result<Option<Tag>, String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let error = |e: &dyn std::fmt::Display| {
+        format!("Error reading ID3 tag from `{}`: {}", path.display(), e)
+    };
+
+    match Tag::read_from_path(path) {
+        Ok(tag) => return Ok(Some(tag)),
+        Err(Error {
+            kind: ErrorKind::NoTag,
+            ..
+        }) => {}
+        Err(e) => return Err(error(&e)),
+    }
+
+    let mut file = std::fs::File::open(path).map_err(|e| error(&e))?;
+    let file_len = file.metadata().map_err(|e| error(&e))?.len();
+    let head_len = file_len.min(ID3_SCAN_LIMIT);
+    let tail_start = file_len.saturating_sub(ID3_SCAN_LIMIT).max(head_len);
+
+    for (start, end) in [(0, head_len), (tail_start, file_len)] {
+        let mut window = vec![0; (end - start) as usize];
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.read_exact(&mut window))
+            .map_err(|e| error(&e))?;
+
+        for offset in (0..window.len()).filter(|&i| is_id3v2_header(&window[i..])) {
+            let offset = start + offset as u64;
+            if offset == 0 {
+                continue;
+            }
+            file.seek(SeekFrom::Start(offset)).map_err(|e| error(&e))?;
+            if let Ok(tag) = Tag::read_from2(&mut file) {
+                return Ok(Some(tag));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether the bytes start with something that looks like an ID3v2 tag header.
+fn is_id3v2_header(bytes: &[u8]) -> bool {
+    bytes.len() >= 10
+        && bytes.starts_with(b"ID3")
+        && (2..=4).contains(&bytes[3])
+        && bytes[4] != 0xFF
+        && bytes[6..10].iter().all(|byte| *byte < 0x80)
+}
+
+/// [Chapters](crate::Chapter) read from an MP3 file together with the length of the episode.
+///
+/// Returned by [from_mp3_file_ext].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mp3Chapters {
+    /// Chapters, as returned by [from_mp3_file].
+    pub chapters: Vec<Chapter>,
+    /// Length of the audio given by the ID3 `TLEN` frame, if present.
+    pub total_duration: Option<Duration>,
+}
+
+/// Reads [chapters](crate::Chapter) and the length of the episode from MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames.
+///
+/// The chapters are the same as those returned by [from_mp3_file]. The length is read from the
+/// `TLEN` frame (in milliseconds), which can then be passed as the total to, e.g.,
+/// [durations](crate::durations) or [fill_gaps](crate::fill_gaps). The audio itself is not
+/// decoded, so the length is `None` if the tag has no valid `TLEN` frame.
+///
+/// # Example:
+/// ```rust
+/// # use chrono::Duration;
+/// # use id3::TagLike;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let src_path = "tests/data/id3-chapters.jfk-rice-university-speech.mp3";
+/// assert_eq!(chapters::from_mp3_file_ext(src_path).unwrap().total_duration, None);
+///
+/// let dst_path = std::env::temp_dir().join("chapters-doctest-mp3-ext.mp3");
+/// std::fs::copy(src_path, &dst_path).unwrap();
+/// let mut tag = id3::Tag::read_from_path(&dst_path).unwrap();
+/// tag.set_duration(1_065_000);
+/// tag.write_to_path(&dst_path, id3::Version::Id3v24).unwrap();
+///
+/// let mp3 = chapters::from_mp3_file_ext(&dst_path).expect("Failed to parse chapters");
+///
+/// assert_eq!(mp3.chapters, chapters::from_mp3_file(src_path).unwrap());
+/// assert_eq!(mp3.total_duration, Some(Duration::seconds(1065)));
+/// # std::fs::remove_file(&dst_path).unwrap();
+/// # }
+/// ```
+pub fn from_mp3_file_ext<P: AsRef<Path>>(path: P) -> Result<Mp3Chapters, String> {
+    let tag = match read_tag(path.as_ref())? {
+        Some(tag) => tag,
+        None => {
+            return Ok(Mp3Chapters {
+                chapters: Vec::new(),
+                total_duration: None,
+            })
+        }
+    };
+
+    Ok(Mp3Chapters {
+        chapters: chapters_from_tag(&tag, &Mp3Options::default())?,
+        total_duration: tag
+            .duration()
+            .map(|milliseconds| Duration::milliseconds(milliseconds as i64)),
+    })
+}
+
+/// Reads [chapters](crate::Chapter) from MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames without blocking the async runtime.
+///
+/// Only the ID3 tag at the start of the file is read, using [tokio::fs], and it is then parsed on
+/// a blocking thread via [tokio::task::spawn_blocking]; if there is no tag at the start, the whole
+/// search is done on a blocking thread. The result is the same as that of [from_mp3_file].
+/// Requires the `tokio` feature.
+///
+/// # Example:
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let runtime = tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap();
+///
+/// let path = "tests/data/id3-chapters.jfk-rice-university-speech.mp3";
+/// let chapters = runtime
+///     .block_on(chapters::from_mp3_file_async(path))
+///     .expect("Failed to parse chapters");
+///
+/// assert_eq!(chapters, chapters::from_mp3_file(path).unwrap());
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn from_mp3_file_async<P: AsRef<Path>>(path: P) -> Result<Vec<Chapter>, String> {
+    use tokio::io::AsyncReadExt;
+
+    let error = |e: &dyn std::fmt::Display| {
+        format!(
+            "Error reading ID3 tag from `{}`: {}",
+            path.as_ref().display(),
+            e
+        )
+    };
+
+    let mut file = tokio::fs::File::open(path.as_ref())
+        .await
+        .map_err(|e| error(&e))?;
+
+    // The header gives the size of the rest of the tag, so the audio itself is never read.
+    let mut bytes = vec![0; 10];
+    file.read_exact(&mut bytes).await.map_err(|e| error(&e))?;
+    if !bytes.starts_with(b"ID3") {
+        // Searching the file for a tag elsewhere is left to the blocking reader.
+        let path = path.as_ref().to_path_buf();
+        return tokio::task::spawn_blocking(move || from_mp3_file(path))
+            .await
+            .map_err(|e| error(&e))?;
+    }
+    let size = bytes[6..10]
+        .iter()
+        .fold(0, |size, byte| (size << 7) | (*byte as usize & 0x7F));
+    let has_footer = bytes[5] & 0x10 != 0;
+    let mut rest = vec![0; size + if has_footer { 10 } else { 0 }];
+    file.read_exact(&mut rest).await.map_err(|e| error(&e))?;
+    bytes.extend(rest);
+
+    let chapters = tokio::task::spawn_blocking(move || {
+        let tag = Tag::read_from2(std::io::Cursor::new(bytes))?;
+        Ok::<_, Error>(chapters_from_tag(&tag, &Mp3Options::default()))
+    })
+    .await
+    .map_err(|e| error(&e))?
+    .map_err(|e| error(&e))??;
+
+    Ok(chapters)
+}
+
+/// Reads chapters from an already parsed ID3 tag.
+fn chapters_from_tag(tag: &Tag, options: &Mp3Options) -> Result<Vec<Chapter>, String> {
+    let mut chapters = Vec::new();
+
+    // Some encoders store chapter images as top-level pictures described by the chapter's element ID.
+    let pictures: Vec<&id3::frame::Picture> = tag.pictures().collect();
+
+    // Chapters left out of every table of contents are hidden. Without any table of contents, all
+    // chapters are visible.
+    let visible_element_ids: Option<Vec<&String>> = if tag.tables_of_contents().next().is_some() {
+        Some(
+            tag.tables_of_contents()
+                .flat_map(|toc| &toc.elements)
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    // ID3v2.2 has no chapter frames, but some encoders still write `CHP` frames laid out like `CHAP`.
+    let v22_chapters = tag
+        .frames()
+        .filter(|frame| frame.id() == "CHP")
+        .filter_map(|frame| match frame.content() {
+            id3::Content::Unknown(unknown) => Some(&unknown.data[..]),
+            _ => None,
+        })
+        .map(chapter_from_v22_frame)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for id3_chapter in tag.chapters().chain(&v22_chapters) {
+        let start = Duration::milliseconds(id3_chapter.start_time as i64);
+
+        let temp_end = Duration::milliseconds(id3_chapter.end_time as i64);
+        // Some programs might encode chapters as instants, i.e., with the start and end time being
+        // the same, or use all ones for an unknown end time.
+        let end = if id3_chapter.end_time == u32::MAX
+            || (options.instants_have_no_end && temp_end == start)
+        {
+            None
+        } else {
+            Some(temp_end)
+        };
+
+        let mut title = None;
+        let mut language = None;
+        let mut link = None;
+        let mut images = Vec::new();
+
+        for subframe in &id3_chapter.frames {
+            match subframe.content() {
+                // Text frames have no language of their own, so a separate `TLAN` frame is used.
+                id3::Content::Text(text) if ["TLAN", "TLA"].contains(&subframe.id()) => {
+                    language = Some(text.clone());
+                }
+                id3::Content::Text(text) => {
+                    title = Some(text.clone());
+                }
+                // TODO: Check if anyone uses this method as opposed to `ExtendedLink`.
+                id3::Content::Link(url) => {
+                    link = Some(Link {
+                        url: url::Url::parse(url).map_err(|e| e.to_string())?,
+                        title: None,
+                    });
+                }
+                id3::Content::ExtendedLink(extended_link) => {
+                    link = Some(Link {
+                        url: url::Url::parse(&extended_link.link).map_err(|e| e.to_string())?,
+                        title: match extended_link.description.trim() {
+                            "" => None,
+                            description => Some(description.to_string()),
+                        },
+                    });
+                }
+                // The "-->" MIME type means that the picture data is a link to the image.
+                id3::Content::Picture(picture) if picture.mime_type == "-->" => {
+                    let url = String::from_utf8_lossy(&picture.data);
+                    images.push(Image::from(
+                        url::Url::parse(&url).map_err(|e| e.to_string())?,
+                    ));
+                }
+                id3::Content::Picture(picture) => {
+                    images.push(Image::Data {
+                        mime_type: picture.mime_type.clone(),
+                        data: picture.data.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // The first picture is the chapter's main image.
+        let image = if images.is_empty() {
+            None
+        } else {
+            Some(images.remove(0))
+        };
+        let image = image.or_else(|| {
+            pictures
+                .iter()
+                .find(|picture| picture.description == id3_chapter.element_id)
+                .map(|picture| Image::Data {
+                    mime_type: picture.mime_type.clone(),
+                    data: picture.data.clone(),
+                })
+        });
+
+        let hidden = visible_element_ids
+            .as_ref()
+            .is_some_and(|ids| !ids.contains(&&id3_chapter.element_id));
+
+        chapters.push(Chapter {
+            title,
+            language,
+            link,
+            image,
+            images,
+            start,
+            end,
+            hidden,
+            element_id: Some(id3_chapter.element_id.clone()),
+            start_byte: Some(id3_chapter.start_offset).filter(|&offset| offset != u32::MAX),
+            end_byte: Some(id3_chapter.end_offset).filter(|&offset| offset != u32::MAX),
+            ..Default::default()
+        });
+    }
+
+    // Order chapters by start time.
+    chapters.sort();
+
+    Ok(chapters)
+}
+
+/// Returns the ID3 element IDs of the chapters: their own [element_id](crate::Chapter::element_id)
+/// if set, otherwise the prefix followed by a number by position (e.g., "chp1", "chp2", etc.),
+/// skipping IDs that are already taken, including that of the table of contents.
+fn element_ids(chapters: &[Chapter], options: &Mp3WriteOptions) -> Result<Vec<String>, String> {
+    let mut taken = std::collections::HashSet::from([String::from(TOC_ELEMENT_ID)]);
+    for element_id in chapters.iter().filter_map(|c| c.element_id.as_ref()) {
+        if element_id == TOC_ELEMENT_ID {
+            return Err(format!(
+                "Chapter element ID `{}` is reserved for the table of contents",
+                element_id
+            ));
+        }
+        if !taken.insert(element_id.clone()) {
+            return Err(format!("Duplicate chapter element ID `{}`", element_id));
+        }
+    }
+
+    let mut element_ids = Vec::with_capacity(chapters.len());
+    for (i, chapter) in chapters.iter().enumerate() {
+        let element_id = match &chapter.element_id {
+            Some(element_id) => element_id.clone(),
+            None => {
+                let element_id = (options.first_index as u64 + i as u64..)
+                    .map(|n| format!("{}{}", options.element_id_prefix, n))
+                    .find(|element_id| !taken.contains(element_id))
+                    .unwrap();
+                taken.insert(element_id.clone());
+                element_id
+            }
+        };
+        // Element IDs are null-terminated ISO-8859-1 strings.
+        if element_id.is_empty() || element_id.chars().any(|c| c == '\0' || c > '\u{ff}') {
+            return Err(format!(
+                "Chapter element ID `{}` is not a non-empty ISO-8859-1 string without null characters",
+                element_id
+            ));
+        }
+        element_ids.push(element_id);
+    }
+
+    Ok(element_ids)
+}
+
+/// Converts a chapter time to the milliseconds stored in ID3 `CHAP` frames.
+fn id3_milliseconds(time: Duration) -> Result<u32, String> {
+    u32::try_from(time.num_milliseconds()).map_err(|_| {
+        format!(
+            "Chapter time of {} ms is outside the range of ID3 chapter frames (0 to {} ms)",
+            time.num_milliseconds(),
+            u32::MAX
+        )
+    })
+}
+
+/// Decodes the data of an ID3v2.2 `CHP` frame.
+fn chapter_from_v22_frame(data: &[u8]) -> Result<id3::frame::Chapter, String> {
+    let invalid = || String::from("Invalid ID3v2.2 chapter frame");
+
+    let element_id_end = data.iter().position(|&b| b == 0).ok_or_else(invalid)?;
+    let element_id = String::from_utf8_lossy(&data[..element_id_end]).into_owned();
+    let fields = data
+        .get(element_id_end + 1..element_id_end + 17)
+        .ok_or_else(invalid)?;
+    let field = |i: usize| u32::from_be_bytes(fields[i * 4..i * 4 + 4].try_into().unwrap());
+
+    // The subframes are ID3v2.2 frames, which are decoded by wrapping them in a tag header.
+    let subframes = &data[element_id_end + 17..];
+    let frames = if subframes.is_empty() {
+        Vec::new()
+    } else {
+        let size = subframes.len() as u32;
+        let mut tag_data = b"ID3\x02\x00\x00".to_vec();
+        tag_data.extend([size >> 21, size >> 14, size >> 7, size].map(|b| (b & 0x7f) as u8));
+        tag_data.extend_from_slice(subframes);
+        Tag::read_from2(std::io::Cursor::new(tag_data))
+            .map_err(|e| format!("Error reading ID3v2.2 chapter subframes: {}", e))?
+            .frames()
+            .cloned()
+            .collect()
+    };
+
+    Ok(id3::frame::Chapter {
+        element_id,
+        start_time: field(0),
+        end_time: field(1),
+        start_offset: field(2),
+        end_offset: field(3),
+        frames,
+    })
+}
+
+/// Writes [chapters](crate::Chapter) to MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames.
+///
+/// If the file already has chapters, they will be replaced. A table of contents (`CTOC` frame)
+/// listing all chapters except the [hidden](crate::Chapter::hidden) ones is written as well.
+/// Each `CHAP` frame gets the chapter's [element ID](crate::Chapter::element_id), or "chp1",
+/// "chp2", etc. by position if it has none.
+///
+/// A chapter's [language](crate::Chapter::language) is written as a `TLAN` frame in its `CHAP`
+/// frame, since the title frame has no language of its own.
+///
+/// Chapter images are embedded as front cover pictures (`APIC` frames) in their chapters, followed
+/// by any [additional images](crate::Chapter::images) as pictures of type "other". Images
+/// given by [URL](crate::Image::Url) are not downloaded; instead, the URL is stored as the picture
+/// data with the special "-->" MIME type, as allowed by the ID3 specification.
+///
+/// ID3 stores chapter times as 32-bit milliseconds, so an error is returned if a start or end time
+/// is negative or later than about 49.7 days.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// #     let dst_filepath_str = "tests/data/id3-chapters.jfk-rice-university-speech.frames-added.mp3";
+/// #     let dst_filepath = std::path::Path::new(&dst_filepath_str);
+/// #
+/// #     struct Test {
+/// #         src_filepath_str: &'static str,
+/// #     }
+/// #
+/// #     let tests = vec![
+/// #         Test {
+/// #             src_filepath_str: "tests/data/id3-chapters.jfk-rice-university-speech.mp3",
+/// #         },
+/// #         Test {
+/// #             src_filepath_str: "tests/data/id3-chapters.jfk-rice-university-speech.no-frames.mp3",
+/// #         },
+/// #     ];
+/// #
+/// #     for test in tests {
+/// #         let src_filepath = std::path::Path::new(&test.src_filepath_str);
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::seconds(0),
+///         title: Some("Introduction".to_string()),
+///         link: Some(Link{
+///             url: url::Url::parse("https://www.rice.edu").unwrap(),
+///             title: None,
+///         }),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(42),
+///         title: Some("Status quo".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: chrono::Duration::minutes(5) + chrono::Duration::seconds(8),
+///         title: Some(String::from("On being first")),
+///         link: Some(Link{
+///             url: url::Url::parse("https://www.osti.gov/opennet/manhattan-project-history/Events/1945/trinity.htm").unwrap(),
+///             title: Some(String::from("The Trinity Test")),
+///         }),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::to_mp3_file(src_filepath, dst_filepath, &chapters).expect("Failed to write chapters");
+/// #
+/// #         let chapters_read = chapters::from_mp3_file(dst_filepath).expect("Failed to read chapters");
+/// #         let element_ids: Vec<_> = chapters_read.iter().map(|c| c.element_id.clone()).collect();
+/// #         assert_eq!(element_ids, ["chp1", "chp2", "chp3"].map(|id| Some(id.to_string())));
+/// #         let chapters_read: Vec<Chapter> = chapters_read
+/// #             .into_iter()
+/// #             .map(|c| Chapter { element_id: None, ..c })
+/// #             .collect();
+///           # assert_eq!(chapters, chapters_read);
+/// #
+/// #         // Cleanup
+/// #         std::fs::remove_file(dst_filepath).unwrap();
+/// #     }
+/// # }
+/// ```
+pub fn to_mp3_file<P: AsRef<Path>>(
+    src_path: P,
+    dst_path: P,
+    chapters: &[Chapter],
+) -> Result<(), String> {
+    to_mp3_file_with_version(src_path, dst_path, chapters, Version::Id3v24)
+}
+
+/// Writes [chapters](crate::Chapter) to MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames using the given ID3 version.
+///
+/// [to_mp3_file] is equivalent to calling this function with ID3v2.4. Some players (e.g., Windows
+/// Media Player) and podcast hosts only accept ID3v2.3. ID3v2.2 has no chapter frames, so writing
+/// it returns an error.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let src_path = "tests/data/id3-chapters.jfk-rice-university-speech.mp3";
+/// let dst_path = std::env::temp_dir().join("chapters-doctest-id3v23.mp3");
+///
+/// let chapters = vec![Chapter {
+///     start: Duration::seconds(9),
+///     end: Some(Duration::seconds(42)),
+///     title: Some("Thanks".to_string()),
+///     element_id: Some("thanks".to_string()),
+///     ..Default::default()
+/// }];
+/// chapters::to_mp3_file_with_version(
+///     src_path.as_ref(),
+///     dst_path.as_path(),
+///     &chapters,
+///     id3::Version::Id3v23,
+/// )
+/// .expect("Failed to write chapters");
+///
+/// assert_eq!(chapters::from_mp3_file(&dst_path).unwrap(), chapters);
+/// # std::fs::remove_file(&dst_path).unwrap();
+/// # }
+/// ```
+pub fn to_mp3_file_with_version<P: AsRef<Path>>(
+    src_path: P,
+    dst_path: P,
+    chapters: &[Chapter],
+    version: Version,
+) -> Result<(), String> {
+    let options = Mp3WriteOptions {
+        version,
+        ..Default::default()
+    };
+    to_mp3_file_with_options(src_path, dst_path, chapters, &options)
+}
+
+/// Options for [to_mp3_file_with_options].
+#[derive(Debug, Clone)]
+pub struct Mp3WriteOptions {
+    /// ID3 version of the written tag. ID3v2.4 by default.
+    pub version: Version,
+    /// Prefix of the element IDs of chapters that have no [element ID](crate::Chapter::element_id)
+    /// of their own. "chp" by default.
+    pub element_id_prefix: String,
+    /// Number appended to the prefix for the first chapter, increasing by one for each following
+    /// chapter. 1 by default.
+    pub first_index: u32,
+}
+
+impl Default for Mp3WriteOptions {
+    fn default() -> Self {
+        Self {
+            version: Version::Id3v24,
+            element_id_prefix: String::from("chp"),
+            first_index: 1,
+        }
+    }
+}
+
+/// Writes [chapters](crate::Chapter) to MP3 file's [ID3](https://en.wikipedia.org/wiki/ID3) tag frames using the given options.
+///
+/// [to_mp3_file] is equivalent to calling this function with the default options. Element IDs,
+/// whether generated or the chapters' own, must be non-empty ISO-8859-1 strings without null
+/// characters, or an error is returned.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Mp3WriteOptions};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let src_path = "tests/data/id3-chapters.jfk-rice-university-speech.mp3";
+/// let dst_path = std::env::temp_dir().join("chapters-doctest-mp3-write-options.mp3");
+///
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::seconds(9),
+///         title: Some("Thanks".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+/// let options = Mp3WriteOptions {
+///     element_id_prefix: "chapter-".to_string(),
+///     first_index: 0,
+///     ..Default::default()
+/// };
+/// chapters::to_mp3_file_with_options(src_path.as_ref(), dst_path.as_path(), &chapters, &options)
+///     .expect("Failed to write chapters");
+///
+/// let element_ids: Vec<_> = chapters::from_mp3_file(&dst_path)
+///     .unwrap()
+///     .into_iter()
+///     .map(|chapter| chapter.element_id.unwrap())
+///     .collect();
+/// assert_eq!(element_ids, ["chapter-0", "chapter-1"]);
+/// # std::fs::remove_file(&dst_path).unwrap();
+/// # }
+/// ```
+pub fn to_mp3_file_with_options<P: AsRef<Path>>(
+    src_path: P,
+    dst_path: P,
+    chapters: &[Chapter],
+    options: &Mp3WriteOptions,
+) -> Result<(), String> {
+    let version = options.version;
+    if version == Version::Id3v22 {
+        return Err(String::from("Chapters cannot be written to ID3v2.2 tags"));
+    }
+
+    let element_ids = element_ids(chapters, options)?;
+    // Chapters without an end are written as instants.
+    let times = chapters
+        .iter()
+        .map(|chapter| {
+            let start_time = id3_milliseconds(chapter.start)?;
+            let end_time = chapter.end.map(id3_milliseconds).transpose()?;
+            Ok((start_time, end_time.unwrap_or(start_time)))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    std::fs::copy(&src_path, &dst_path).map_err(|e| {
+        format!(
+            "Error copying `{}` to `{}`: {}",
+            src_path.as_ref().display(),
+            dst_path.as_ref().display(),
+            e
+        )
+    })?;
+
+    let mut tag = match Tag::read_from_path(&src_path) {
+        Ok(mut tag) => {
+            tag.remove_all_chapters();
+            tag.remove_all_tables_of_contents();
+            tag
+        }
+        Err(Error {
+            kind: ErrorKind::NoTag,
+            ..
+        }) => Tag::new(),
+        Err(err) => {
+            return Err(format!(
+                "Error reading ID3 tag from `{}`: {}",
+                src_path.as_ref().display(),
+                err
+            ))
+        }
+    };
+
+    for ((chapter, element_id), (start_time, end_time)) in
+        chapters.iter().zip(&element_ids).zip(times)
+    {
+        let mut id3_chapter = id3::frame::Chapter {
+            element_id: element_id.clone(),
+            start_time,
+            end_time,
+            // All ones means that there is no byte offset.
+            start_offset: chapter.start_byte.unwrap_or(u32::MAX),
+            end_offset: chapter.end_byte.unwrap_or(u32::MAX),
+            frames: Vec::new(),
+        };
+
+        if let Some(title) = &chapter.title {
+            let frame = id3::frame::Frame::with_content("TIT2", id3::Content::Text(title.clone()));
+            id3_chapter.frames.push(frame);
+        }
+
+        if let Some(language) = &chapter.language {
+            let frame =
+                id3::frame::Frame::with_content("TLAN", id3::Content::Text(language.clone()));
+            id3_chapter.frames.push(frame);
+        }
+
+        if let Some(link) = &chapter.link {
+            // title or "" if None
+            let link_title = link.title.as_ref().map_or("", |t| t.as_str());
+            let frame = id3::frame::Frame::with_content(
+                "WXXX",
+                id3::Content::ExtendedLink(id3::frame::ExtendedLink {
+                    link: link.url.to_string(),
+                    description: link_title.to_string(),
+                }),
+            );
+            id3_chapter.frames.push(frame);
+        }
+
+        for (i, image) in chapter.image.iter().chain(&chapter.images).enumerate() {
+            let (mime_type, data) = match image {
+                Image::Url { url, .. } => (String::from("-->"), url.to_string().into_bytes()),
+                Image::Data { mime_type, data } => (mime_type.clone(), data.clone()),
+            };
+            // Pictures in a frame must have distinct descriptions, so additional images are numbered.
+            let (picture_type, description) = match i {
+                0 => (id3::frame::PictureType::CoverFront, String::new()),
+                i => (id3::frame::PictureType::Other, (i + 1).to_string()),
+            };
+            let frame = id3::frame::Frame::with_content(
+                "APIC",
+                id3::Content::Picture(id3::frame::Picture {
+                    mime_type,
+                    picture_type,
+                    description,
+                    data,
+                }),
+            );
+            id3_chapter.frames.push(frame);
+        }
+
+        tag.add_frame(id3::frame::Frame::with_content(
+            "CHAP",
+            id3::Content::Chapter(id3_chapter),
+        ));
+    }
+
+    // Hidden chapters are left out of the table of contents.
+    if !chapters.is_empty() {
+        let toc = id3::frame::TableOfContents {
+            element_id: String::from(TOC_ELEMENT_ID),
+            top_level: true,
+            ordered: true,
+            elements: chapters
+                .iter()
+                .zip(&element_ids)
+                .filter(|(chapter, _)| !chapter.hidden)
+                .map(|(_, element_id)| element_id.clone())
+                .collect(),
+            frames: Vec::new(),
+        };
+        tag.add_frame(id3::frame::Frame::with_content(
+            "CTOC",
+            id3::Content::TableOfContents(toc),
+        ));
+    }
+
+    tag.write_to_path(&dst_path, version).map_err(|e| {
+        format!(
+            "Error writing ID3  tag to `{}`: {}",
+            dst_path.as_ref().display(),
+            e
+        )
+    })?;
+
+    Ok(())
+}
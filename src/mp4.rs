@@ -0,0 +1,717 @@
+use crate::Chapter;
+use chrono::Duration;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// A box (also called an atom) found at the top level of an MP4 file.
+struct TopLevelBox {
+    kind: [u8; 4],
+    offset: u64,
+    header_len: u64,
+    size: u64,
+}
+
+/// A box found inside another box's payload.
+struct ChildBox<'a> {
+    kind: [u8; 4],
+    /// The whole box, including its header.
+    raw: &'a [u8],
+    /// Where the payload starts, relative to the start of the parent's payload.
+    payload_offset: usize,
+    payload: &'a [u8],
+}
+
+/// Reads [chapters](crate::Chapter) from an MP4/M4A/M4B file.
+///
+/// Chapters are read from the [Nero](https://en.wikipedia.org/wiki/Nero_Digital) `chpl` atom if
+/// the file has one, and otherwise from the QuickTime chapter track, i.e., the text track
+/// referenced by another track's `chap` reference. Nero chapters only carry start times, whereas
+/// QuickTime chapters also get their end times from the text samples' durations.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = chapters::from_mp4_file("tests/data/mp4-chapters.nero.m4a")
+///     .expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::seconds(0),
+///             title: Some(String::from("Intro")),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::seconds(5),
+///             title: Some(String::from("Baboons")),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::milliseconds(12_500),
+///             title: Some(String::from("Steve Jobs — 日本")),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// #
+/// # let chapters = chapters::from_mp4_file("tests/data/mp4-chapters.quicktime.m4a")
+/// #     .expect("Failed to parse chapters");
+/// #
+/// # assert_eq!(
+/// #     chapters,
+/// #     vec![
+/// #         Chapter {
+/// #             start: Duration::seconds(0),
+/// #             end: Some(Duration::seconds(5)),
+/// #             title: Some(String::from("Intro")),
+/// #             ..Default::default()
+/// #         },
+/// #         Chapter {
+/// #             start: Duration::seconds(5),
+/// #             end: Some(Duration::milliseconds(12_500)),
+/// #             title: Some(String::from("Baboons")),
+/// #             ..Default::default()
+/// #         },
+/// #         Chapter {
+/// #             start: Duration::milliseconds(12_500),
+/// #             end: Some(Duration::seconds(20)),
+/// #             title: Some(String::from("Steve Jobs — 日本")),
+/// #             ..Default::default()
+/// #         },
+/// #     ]
+/// # );
+/// # }
+/// ```
+pub fn from_mp4_file<P: AsRef<Path>>(path: P) -> Result<Vec<Chapter>, String> {
+    let error = |e: String| {
+        format!(
+            "Error reading MP4 file `{}`: {}",
+            path.as_ref().display(),
+            e
+        )
+    };
+
+    let mut file = File::open(&path).map_err(|e| error(e.to_string()))?;
+    let top_level_boxes = read_top_level_boxes(&mut file).map_err(error)?;
+    let moov = top_level_boxes
+        .iter()
+        .find(|b| &b.kind == b"moov")
+        .ok_or_else(|| error(String::from("no `moov` box found")))?;
+    let moov = read_payload(&mut file, moov).map_err(error)?;
+
+    if let Some(chpl) = find_path(&moov, &[b"udta", b"chpl"]).map_err(error)? {
+        return parse_nero_chapters(chpl).map_err(error);
+    }
+
+    read_quicktime_chapters(&mut file, &moov).map_err(error)
+}
+
+/// Writes [chapters](crate::Chapter) to an MP4/M4A/M4B file.
+///
+/// The source file is copied to `dst_path` with the chapters stored in a
+/// [Nero](https://en.wikipedia.org/wiki/Nero_Digital) `chpl` atom inside `moov/udta`. Nero
+/// chapters the file already has are replaced, whereas QuickTime chapter tracks are left untouched;
+/// since [from_mp4_file](crate::from_mp4_file) prefers Nero chapters, the written chapters are the
+/// ones read back. An empty list of chapters removes the `chpl` atom altogether.
+///
+/// Only the start time and the title (as UTF-8, cut to 255 bytes) of each chapter are stored. The
+/// `chpl` atom can hold at most 255 chapters.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// #     let src_filepath = std::path::Path::new("tests/data/mp4-chapters.quicktime.m4a");
+/// #     let dst_filepath = std::path::Path::new("tests/data/mp4-chapters.quicktime.chpl-added.m4a");
+/// #
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::seconds(0),
+///         title: Some("Introduction".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::milliseconds(4_200),
+///         title: Some("Über alles".to_string()),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::to_mp4_file(src_filepath, dst_filepath, &chapters).expect("Failed to write chapters");
+/// #
+/// # let chapters_read = chapters::from_mp4_file(dst_filepath).expect("Failed to read chapters");
+/// # assert_eq!(chapters, chapters_read);
+/// #
+/// # // Cleanup
+/// # std::fs::remove_file(dst_filepath).unwrap();
+/// # }
+/// ```
+pub fn to_mp4_file<P: AsRef<Path>>(
+    src_path: P,
+    dst_path: P,
+    chapters: &[Chapter],
+) -> Result<(), String> {
+    let chpl = nero_chapters_box(chapters)?;
+
+    let read_error = |e: String| {
+        format!(
+            "Error reading MP4 file `{}`: {}",
+            src_path.as_ref().display(),
+            e
+        )
+    };
+    let mut src = File::open(&src_path).map_err(|e| read_error(e.to_string()))?;
+    let top_level_boxes = read_top_level_boxes(&mut src).map_err(read_error)?;
+    let moov = top_level_boxes
+        .iter()
+        .find(|b| &b.kind == b"moov")
+        .ok_or_else(|| read_error(String::from("no `moov` box found")))?;
+    let old_moov = read_payload(&mut src, moov).map_err(read_error)?;
+
+    let mut new_moov = replace_nero_chapters(&old_moov, chpl.as_deref()).map_err(read_error)?;
+
+    // If the media data comes after `moov`, it moves by however much `moov` has grown or shrunk.
+    let delta = box_len(new_moov.len()) as i64 - moov.size as i64;
+    shift_chunk_offsets(&mut new_moov, moov.offset + moov.size, delta).map_err(read_error)?;
+
+    // Write to a temporary file first so that the source can also be the destination.
+    let tmp_path = {
+        let mut file_name = dst_path
+            .as_ref()
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string();
+        file_name.push(".tmp");
+        dst_path.as_ref().with_file_name(file_name)
+    };
+    let write_error = |e: std::io::Error| {
+        format!(
+            "Error writing MP4 file `{}`: {}",
+            dst_path.as_ref().display(),
+            e
+        )
+    };
+    let mut dst = File::create(&tmp_path).map_err(write_error)?;
+
+    let mut end = 0;
+    for top_level_box in &top_level_boxes {
+        if &top_level_box.kind == b"moov" {
+            dst.write_all(&encode_box(b"moov", &new_moov))
+                .map_err(write_error)?;
+        } else {
+            src.seek(SeekFrom::Start(top_level_box.offset))
+                .map_err(write_error)?;
+            std::io::copy(&mut (&mut src).take(top_level_box.size), &mut dst)
+                .map_err(write_error)?;
+        }
+        end = top_level_box.offset + top_level_box.size;
+    }
+    // Keep any trailing bytes that are too short to be a box.
+    src.seek(SeekFrom::Start(end)).map_err(write_error)?;
+    std::io::copy(&mut src, &mut dst).map_err(write_error)?;
+    drop(dst);
+
+    std::fs::rename(&tmp_path, &dst_path).map_err(write_error)?;
+
+    Ok(())
+}
+
+/// Lists the boxes at the top level of an MP4 file.
+fn read_top_level_boxes(file: &mut File) -> Result<Vec<TopLevelBox>, String> {
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let mut boxes = Vec::new();
+    let mut offset = 0;
+    while offset + 8 <= file_len {
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| e.to_string())?;
+        let mut header = [0; 16];
+        file.read_exact(&mut header[..8])
+            .map_err(|e| e.to_string())?;
+        let kind = bytes::<4>(&header, 4)?;
+        let (header_len, size) = match u32::from_be_bytes(bytes(&header, 0)?) {
+            // The box extends to the end of the file.
+            0 => (8, file_len - offset),
+            // The size is stored as a 64-bit integer after the box type.
+            1 => {
+                file.read_exact(&mut header[8..])
+                    .map_err(|e| e.to_string())?;
+                (16, u64::from_be_bytes(bytes(&header, 8)?))
+            }
+            size => (8, u64::from(size)),
+        };
+        // `offset` never exceeds `file_len`, so the subtraction can't overflow, unlike `offset + size`.
+        if size < header_len || size > file_len - offset {
+            return Err(format!(
+                "box `{}` at offset {} has an invalid size of {} bytes",
+                String::from_utf8_lossy(&kind),
+                offset,
+                size
+            ));
+        }
+        boxes.push(TopLevelBox {
+            kind,
+            offset,
+            header_len,
+            size,
+        });
+        offset += size;
+    }
+
+    Ok(boxes)
+}
+
+/// Reads the payload of a top-level box into memory.
+fn read_payload(file: &mut File, top_level_box: &TopLevelBox) -> Result<Vec<u8>, String> {
+    file.seek(SeekFrom::Start(
+        top_level_box.offset + top_level_box.header_len,
+    ))
+    .map_err(|e| e.to_string())?;
+    let mut payload = vec![0; (top_level_box.size - top_level_box.header_len) as usize];
+    file.read_exact(&mut payload).map_err(|e| e.to_string())?;
+    Ok(payload)
+}
+
+/// Splits a container box's payload into its child boxes.
+fn child_boxes(data: &[u8]) -> Result<Vec<ChildBox<'_>>, String> {
+    let mut boxes = Vec::new();
+    let mut rest = data;
+    let mut offset = 0;
+    while rest.len() >= 8 {
+        let kind = bytes::<4>(rest, 4)?;
+        let (header_len, size) = match u32::from_be_bytes(bytes(rest, 0)?) {
+            0 => (8, rest.len() as u64),
+            1 => (16, u64::from_be_bytes(bytes(rest, 8)?)),
+            size => (8, u64::from(size)),
+        };
+        if size < header_len || size > rest.len() as u64 {
+            return Err(format!(
+                "box `{}` has an invalid size of {} bytes",
+                String::from_utf8_lossy(&kind),
+                size
+            ));
+        }
+        boxes.push(ChildBox {
+            kind,
+            raw: &rest[..size as usize],
+            payload_offset: offset + header_len as usize,
+            payload: &rest[header_len as usize..size as usize],
+        });
+        rest = &rest[size as usize..];
+        offset += size as usize;
+    }
+    Ok(boxes)
+}
+
+/// Finds the payload of the first box nested along `path`.
+fn find_path<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Result<Option<&'a [u8]>, String> {
+    let mut payload = data;
+    for kind in path {
+        match child_boxes(payload)?.into_iter().find(|b| &b.kind == *kind) {
+            Some(child) => payload = child.payload,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(payload))
+}
+
+/// Reads `N` bytes at `offset`.
+fn bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], String> {
+    data.get(offset..offset + N)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| String::from("unexpected end of box"))
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, String> {
+    Ok(bytes::<1>(data, offset)?[0])
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    Ok(u16::from_be_bytes(bytes(data, offset)?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    Ok(u32::from_be_bytes(bytes(data, offset)?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, String> {
+    Ok(u64::from_be_bytes(bytes(data, offset)?))
+}
+
+/// Turns an empty title into `None`.
+fn non_empty(title: String) -> Option<String> {
+    if title.is_empty() {
+        None
+    } else {
+        Some(title)
+    }
+}
+
+/// Parses the payload of a Nero `chpl` box.
+///
+/// Start times are stored in units of 100 nanoseconds, followed by a length-prefixed UTF-8 title.
+fn parse_nero_chapters(chpl: &[u8]) -> Result<Vec<Chapter>, String> {
+    let version = read_u8(chpl, 0)?;
+    // Version 1 has four reserved bytes after the version and flags.
+    let mut offset = if version == 0 { 4 } else { 8 };
+
+    let count = read_u8(chpl, offset)?;
+    offset += 1;
+
+    let mut chapters = Vec::new();
+    for _ in 0..count {
+        let start = read_u64(chpl, offset)?;
+        let title_len = read_u8(chpl, offset + 8)? as usize;
+        offset += 9;
+        let title = chpl
+            .get(offset..offset + title_len)
+            .ok_or_else(|| String::from("unexpected end of `chpl` box"))?;
+        offset += title_len;
+
+        chapters.push(Chapter {
+            start: Duration::microseconds((start / 10) as i64),
+            title: non_empty(String::from_utf8_lossy(title).into_owned()),
+            ..Default::default()
+        });
+    }
+
+    chapters.sort();
+
+    Ok(chapters)
+}
+
+/// Returns the length of a box with a payload of `payload_len` bytes.
+fn box_len(payload_len: usize) -> u64 {
+    if payload_len + 8 <= u32::MAX as usize {
+        payload_len as u64 + 8
+    } else {
+        payload_len as u64 + 16
+    }
+}
+
+/// Prepends a box header to `payload`.
+fn encode_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let len = box_len(payload.len());
+    let mut encoded = Vec::with_capacity(len as usize);
+    if len <= u32::MAX as u64 {
+        encoded.extend_from_slice(&(len as u32).to_be_bytes());
+        encoded.extend_from_slice(kind);
+    } else {
+        encoded.extend_from_slice(&1u32.to_be_bytes());
+        encoded.extend_from_slice(kind);
+        encoded.extend_from_slice(&len.to_be_bytes());
+    }
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+/// Builds the payload of a Nero `chpl` box, or `None` if there are no chapters.
+fn nero_chapters_box(chapters: &[Chapter]) -> Result<Option<Vec<u8>>, String> {
+    if chapters.is_empty() {
+        return Ok(None);
+    }
+    let count = u8::try_from(chapters.len()).map_err(|_| {
+        format!(
+            "Nero chapters are limited to 255, but there are {}",
+            chapters.len()
+        )
+    })?;
+
+    // Version 1, no flags, and four reserved bytes.
+    let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, count];
+    for chapter in chapters {
+        let start = chapter
+            .start
+            .num_microseconds()
+            .and_then(|microseconds| u64::try_from(microseconds).ok())
+            // Nero chapters count in units of 100 nanoseconds.
+            .and_then(|microseconds| microseconds.checked_mul(10))
+            .ok_or_else(|| format!("Chapter start `{}` cannot be stored", chapter.start))?;
+        chpl.extend_from_slice(&start.to_be_bytes());
+
+        let mut title = chapter.title.as_deref().unwrap_or_default();
+        if title.len() > u8::MAX as usize {
+            let mut len = u8::MAX as usize;
+            while !title.is_char_boundary(len) {
+                len -= 1;
+            }
+            title = &title[..len];
+        }
+        chpl.push(title.len() as u8);
+        chpl.extend_from_slice(title.as_bytes());
+    }
+
+    Ok(Some(chpl))
+}
+
+/// Rebuilds the payload of `moov` with `chpl` as its only Nero chapters.
+fn replace_nero_chapters(moov: &[u8], chpl: Option<&[u8]>) -> Result<Vec<u8>, String> {
+    let new_udta = |udta: &[u8]| -> Result<Vec<u8>, String> {
+        let mut payload = Vec::new();
+        for child in child_boxes(udta)? {
+            if &child.kind != b"chpl" {
+                payload.extend_from_slice(child.raw);
+            }
+        }
+        if let Some(chpl) = chpl {
+            payload.extend_from_slice(&encode_box(b"chpl", chpl));
+        }
+        Ok(encode_box(b"udta", &payload))
+    };
+
+    let mut payload = Vec::new();
+    let mut has_udta = false;
+    for child in child_boxes(moov)? {
+        if &child.kind == b"udta" && !has_udta {
+            payload.extend_from_slice(&new_udta(child.payload)?);
+            has_udta = true;
+        } else {
+            payload.extend_from_slice(child.raw);
+        }
+    }
+    if !has_udta && chpl.is_some() {
+        payload.extend_from_slice(&new_udta(&[])?);
+    }
+
+    Ok(payload)
+}
+
+/// Moves every chunk offset in `moov`'s `stco` and `co64` tables that points at or past
+/// `boundary` by `delta` bytes.
+fn shift_chunk_offsets(moov: &mut [u8], boundary: u64, delta: i64) -> Result<(), String> {
+    if delta == 0 {
+        return Ok(());
+    }
+
+    let mut tables = Vec::new();
+    for trak in child_boxes(moov)?.iter().filter(|b| &b.kind == b"trak") {
+        let mut offset = trak.payload_offset;
+        let mut payload = trak.payload;
+        for kind in [b"mdia", b"minf", b"stbl"] {
+            match child_boxes(payload)?.into_iter().find(|b| &b.kind == kind) {
+                Some(child) => {
+                    offset += child.payload_offset;
+                    payload = child.payload;
+                }
+                None => break,
+            }
+        }
+        for table in child_boxes(payload)? {
+            if &table.kind == b"stco" || &table.kind == b"co64" {
+                tables.push((table.kind, offset + table.payload_offset));
+            }
+        }
+    }
+
+    let shift = |chunk_offset: u64| {
+        if chunk_offset < boundary {
+            Some(chunk_offset)
+        } else {
+            chunk_offset.checked_add_signed(delta)
+        }
+    };
+    for (kind, offset) in tables {
+        for i in 0..read_u32(moov, offset + 4)? as usize {
+            if &kind == b"stco" {
+                let at = offset + 8 + 4 * i;
+                let chunk_offset = shift(u64::from(read_u32(moov, at)?))
+                    .and_then(|chunk_offset| u32::try_from(chunk_offset).ok())
+                    .ok_or_else(|| String::from("chunk offset does not fit in `stco` box"))?;
+                moov[at..at + 4].copy_from_slice(&chunk_offset.to_be_bytes());
+            } else {
+                let at = offset + 8 + 8 * i;
+                let chunk_offset = shift(read_u64(moov, at)?)
+                    .ok_or_else(|| String::from("chunk offset does not fit in `co64` box"))?;
+                moov[at..at + 8].copy_from_slice(&chunk_offset.to_be_bytes());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads chapters from the text track referenced by another track's `tref/chap` box.
+fn read_quicktime_chapters(file: &mut File, moov: &[u8]) -> Result<Vec<Chapter>, String> {
+    let traks: Vec<&[u8]> = child_boxes(moov)?
+        .into_iter()
+        .filter(|b| &b.kind == b"trak")
+        .map(|b| b.payload)
+        .collect();
+
+    let mut chapter_track_ids = Vec::new();
+    for trak in &traks {
+        if let Some(chap) = find_path(trak, &[b"tref", b"chap"])? {
+            for offset in (0..chap.len()).step_by(4) {
+                chapter_track_ids.push(read_u32(chap, offset)?);
+            }
+        }
+    }
+
+    for trak in &traks {
+        let Some(tkhd) = find_path(trak, &[b"tkhd"])? else {
+            continue;
+        };
+        let track_id = if read_u8(tkhd, 0)? == 0 {
+            read_u32(tkhd, 12)?
+        } else {
+            read_u32(tkhd, 20)?
+        };
+        if chapter_track_ids.contains(&track_id) {
+            return read_text_track(file, trak);
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Reads every sample of a QuickTime text track as a chapter.
+fn read_text_track(file: &mut File, trak: &[u8]) -> Result<Vec<Chapter>, String> {
+    let missing = |kind: &str| format!("chapter track has no `{}` box", kind);
+
+    let mdhd = find_path(trak, &[b"mdia", b"mdhd"])?.ok_or_else(|| missing("mdhd"))?;
+    let timescale = if read_u8(mdhd, 0)? == 0 {
+        read_u32(mdhd, 12)?
+    } else {
+        read_u32(mdhd, 20)?
+    };
+    if timescale == 0 {
+        return Err(String::from("chapter track has a timescale of 0"));
+    }
+
+    let stbl = find_path(trak, &[b"mdia", b"minf", b"stbl"])?.ok_or_else(|| missing("stbl"))?;
+    let stts = find_path(stbl, &[b"stts"])?.ok_or_else(|| missing("stts"))?;
+    let stsz = find_path(stbl, &[b"stsz"])?.ok_or_else(|| missing("stsz"))?;
+    let stsc = find_path(stbl, &[b"stsc"])?.ok_or_else(|| missing("stsc"))?;
+
+    let sample_size = read_u32(stsz, 4)?;
+    let sample_count = read_u32(stsz, 8)? as usize;
+    // With a fixed sample size, the count isn't bounded by the size of the box, so it is checked
+    // against the size of the file instead.
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+    if u64::from(sample_size) * sample_count as u64 > file_len {
+        return Err(String::from(
+            "chapter track has more samples than fit in the file",
+        ));
+    }
+
+    // Sample counts in `stts` come from the file, so they are capped at the number of samples to
+    // avoid huge allocations.
+    let mut durations = Vec::new();
+    for i in 0..read_u32(stts, 4)? as usize {
+        let count = read_u32(stts, 8 + 8 * i)? as usize;
+        let delta = read_u32(stts, 12 + 8 * i)?;
+        let count = count.min(sample_count - durations.len());
+        durations.extend(std::iter::repeat_n(u64::from(delta), count));
+    }
+
+    let sizes = (0..sample_count)
+        .map(|i| match sample_size {
+            0 => read_u32(stsz, 12 + 4 * i),
+            size => Ok(size),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let chunk_offsets = if let Some(stco) = find_path(stbl, &[b"stco"])? {
+        (0..read_u32(stco, 4)? as usize)
+            .map(|i| read_u32(stco, 8 + 4 * i).map(u64::from))
+            .collect::<Result<Vec<_>, _>>()?
+    } else if let Some(co64) = find_path(stbl, &[b"co64"])? {
+        (0..read_u32(co64, 4)? as usize)
+            .map(|i| read_u64(co64, 8 + 8 * i))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        return Err(missing("stco"));
+    };
+
+    // Samples are laid out back to back within chunks; `stsc` tells how many samples each chunk has.
+    let mut samples_per_chunk = Vec::new();
+    for i in 0..read_u32(stsc, 4)? as usize {
+        let first_chunk = read_u32(stsc, 8 + 12 * i)?;
+        let samples = read_u32(stsc, 12 + 12 * i)?;
+        samples_per_chunk.push((first_chunk as usize, samples as usize));
+    }
+    let mut sample_offsets = Vec::new();
+    for (i, chunk_offset) in chunk_offsets.iter().enumerate() {
+        let samples = samples_per_chunk
+            .iter()
+            .rev()
+            .find(|(first_chunk, _)| *first_chunk <= i + 1)
+            .map_or(0, |(_, samples)| *samples);
+        let mut offset = Some(*chunk_offset);
+        for size in sizes.iter().skip(sample_offsets.len()).take(samples) {
+            let sample_offset = offset.ok_or_else(|| {
+                String::from("chapter track has a sample offset that doesn't fit in 64 bits")
+            })?;
+            sample_offsets.push(sample_offset);
+            offset = sample_offset.checked_add(u64::from(*size));
+        }
+    }
+
+    let to_duration = |time: u64| {
+        i64::try_from(u128::from(time) * 1_000_000 / u128::from(timescale))
+            .map(Duration::microseconds)
+            .map_err(|_| format!("chapter time of {} units is out of range", time))
+    };
+
+    let mut chapters = Vec::new();
+    let mut time: u64 = 0;
+    for ((offset, size), duration) in sample_offsets.iter().zip(&sizes).zip(&durations) {
+        // Sizes in `stsz` come from the file, so each sample is checked to lie within the file
+        // before allocating a buffer for it.
+        if offset
+            .checked_add(u64::from(*size))
+            .is_none_or(|end| end > file_len)
+        {
+            return Err(format!(
+                "chapter sample at offset {} with a size of {} bytes doesn't fit in the file",
+                offset, size
+            ));
+        }
+        let end = time
+            .checked_add(*duration)
+            .ok_or_else(|| String::from("chapter track is too long"))?;
+
+        let mut sample = vec![0; *size as usize];
+        file.seek(SeekFrom::Start(*offset))
+            .map_err(|e| e.to_string())?;
+        file.read_exact(&mut sample).map_err(|e| e.to_string())?;
+
+        chapters.push(Chapter {
+            start: to_duration(time)?,
+            end: Some(to_duration(end)?),
+            title: non_empty(parse_text_sample(&sample)?),
+            ..Default::default()
+        });
+        time = end;
+    }
+
+    Ok(chapters)
+}
+
+/// Decodes a text sample, which is a 16-bit length followed by UTF-8 or UTF-16 text.
+fn parse_text_sample(sample: &[u8]) -> Result<String, String> {
+    let len = read_u16(sample, 0)? as usize;
+    let text = sample
+        .get(2..2 + len)
+        .ok_or_else(|| String::from("unexpected end of text sample"))?;
+
+    match text {
+        [0xfe, 0xff, utf16 @ ..] => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            Ok(String::from_utf16_lossy(&units))
+        }
+        utf8 => Ok(String::from_utf8_lossy(utf8).into_owned()),
+    }
+}
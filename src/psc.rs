@@ -0,0 +1,242 @@
+use crate::{Chapter, Image, Link};
+use chrono::Duration;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+
+/// Reads [chapters](crate::Chapter) from [Podlove Simple Chapters](https://podlove.org/simple-chapters/) (PSC) XML.
+///
+/// Every `<psc:chapter>` element becomes a chapter, with the `start` attribute (in normal play
+/// time, e.g., "00:01:23.500") used as the start time, `title` as the title, `href` as the link,
+/// and `image` as the image. Missing optional attributes are mapped to `None`.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Image, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let psc = r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <psc:chapters version="1.2" xmlns:psc="http://podlove.org/simple-chapters">
+///   <psc:chapter start="00:00:00.000" title="Welcome" />
+///   <psc:chapter start="00:01:23.500" title="Baboons &amp; apes" href="https://example.com/baboons" image="https://example.com/baboons.jpg" />
+///   <psc:chapter start="05:04" />
+/// </psc:chapters>"#;
+///
+/// let chapters = chapters::from_psc(psc.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Welcome".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(1) + Duration::seconds(23) + Duration::milliseconds(500),
+///             title: Some("Baboons & apes".to_string()),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com/baboons").unwrap(),
+///                 title: None,
+///             }),
+///             image: Some(Image::from(
+///                 url::Url::parse("https://example.com/baboons.jpg").unwrap()
+///             )),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5) + Duration::seconds(4),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_psc<R: std::io::Read>(reader: R) -> Result<Vec<Chapter>, String> {
+    let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(reader));
+    let mut buf = Vec::new();
+    let mut chapters = Vec::new();
+
+    loop {
+        match reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("Error reading PSC XML: {}", e))?
+        {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"chapter" =>
+            {
+                let mut start = None;
+                let mut chapter = Chapter::default();
+
+                for attribute in element.attributes() {
+                    let attribute = attribute.map_err(|e| e.to_string())?;
+                    let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+                    match attribute.key.local_name().as_ref() {
+                        b"start" => start = Some(parse_normal_play_time(&value)?),
+                        b"title" => chapter.title = Some(value.to_string()),
+                        b"href" => {
+                            chapter.link = Some(Link {
+                                url: url::Url::parse(&value).map_err(|e| e.to_string())?,
+                                title: None,
+                            })
+                        }
+                        b"image" => {
+                            chapter.image = Some(Image::from(
+                                url::Url::parse(&value).map_err(|e| e.to_string())?,
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+
+                chapter.start = start.ok_or("Chapter is missing the `start` attribute")?;
+                chapters.push(chapter);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(chapters)
+}
+
+/// Writes [chapters](crate::Chapter) to [Podlove Simple Chapters](https://podlove.org/simple-chapters/) (PSC) XML.
+///
+/// Every chapter becomes a `<psc:chapter>` element with the start time written as normal play
+/// time (`HH:MM:SS.mmm`) in the `start` attribute, and the title, link URL, and image URL in the
+/// `title`, `href`, and `image` attributes. Since PSC has no notion of hidden chapters, hidden
+/// chapters are omitted.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Image, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Welcome".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(1) + Duration::seconds(23) + Duration::milliseconds(500),
+///         title: Some("Baboons & \"apes\"".to_string()),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com/baboons").unwrap(),
+///             title: None,
+///         }),
+///         image: Some(Image::from(
+///             url::Url::parse("https://example.com/baboons.jpg").unwrap()
+///         )),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(2),
+///         title: Some("Hidden chapter".to_string()),
+///         hidden: true,
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let psc = chapters::to_psc(&chapters).expect("Failed to write chapters");
+///
+/// assert_eq!(psc, r#"<psc:chapters version="1.2" xmlns:psc="http://podlove.org/simple-chapters">
+///   <psc:chapter start="00:00:00.000" title="Welcome" />
+///   <psc:chapter start="00:01:23.500" title="Baboons &amp; &quot;apes&quot;" href="https://example.com/baboons" image="https://example.com/baboons.jpg" />
+/// </psc:chapters>"#);
+/// # }
+/// ```
+pub fn to_psc(chapters: &[Chapter]) -> Result<String, String> {
+    let mut psc = String::from(
+        "<psc:chapters version=\"1.2\" xmlns:psc=\"http://podlove.org/simple-chapters\">\n",
+    );
+
+    for chapter in chapters.iter().filter(|chapter| !chapter.hidden) {
+        psc.push_str(&format!(
+            "  <psc:chapter start=\"{}\"",
+            format_normal_play_time(chapter.start)?
+        ));
+        if let Some(title) = &chapter.title {
+            psc.push_str(&format!(" title=\"{}\"", escape(title)));
+        }
+        if let Some(link) = &chapter.link {
+            psc.push_str(&format!(" href=\"{}\"", escape(link.url.as_str())));
+        }
+        if let Some(Image::Url { url, .. }) = &chapter.image {
+            psc.push_str(&format!(" image=\"{}\"", escape(url.as_str())));
+        }
+        psc.push_str(" />\n");
+    }
+
+    psc.push_str("</psc:chapters>");
+
+    Ok(psc)
+}
+
+/// Parses [normal play time](https://www.ietf.org/rfc/rfc2326.txt) as used by PSC, i.e.,
+/// `[[HH:]MM:]SS[.mmm]`.
+fn parse_normal_play_time(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid PSC timestamp `{}`", s);
+
+    let (whole, fraction) = match s.trim().split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (s.trim(), None),
+    };
+
+    let mut seconds: i64 = 0;
+    let parts: Vec<&str> = whole.split(':').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    for (i, part) in parts.into_iter().enumerate() {
+        if !is_number(part) {
+            return Err(invalid());
+        }
+        let part = part.parse::<i64>().map_err(|_| invalid())?;
+        // Minutes and seconds following a larger unit must be below 60.
+        if i > 0 && part >= 60 {
+            return Err(invalid());
+        }
+        seconds = seconds
+            .checked_mul(60)
+            .and_then(|seconds| seconds.checked_add(part))
+            .ok_or_else(invalid)?;
+    }
+
+    let milliseconds = match fraction {
+        Some(fraction) if is_number(fraction) && fraction.len() <= 3 => format!("{:0<3}", fraction)
+            .parse::<i64>()
+            .map_err(|_| invalid())?,
+        Some(_) => return Err(invalid()),
+        None => 0,
+    };
+
+    Duration::try_seconds(seconds)
+        .and_then(|duration| duration.checked_add(&Duration::milliseconds(milliseconds)))
+        .ok_or_else(invalid)
+}
+
+/// Formats a duration as normal play time with hours and milliseconds, i.e., `HH:MM:SS.mmm`.
+fn format_normal_play_time(duration: Duration) -> Result<String, String> {
+    if duration < Duration::zero() {
+        return Err(format!(
+            "Negative chapter start time ({} ms) cannot be written to PSC",
+            duration.num_milliseconds()
+        ));
+    }
+
+    let milliseconds = duration.num_milliseconds();
+    let hours = milliseconds / 3_600_000;
+    let minutes = milliseconds / 60_000 % 60;
+    let seconds = milliseconds / 1000 % 60;
+    let milliseconds = milliseconds % 1000;
+
+    Ok(format!(
+        "{hours:02}:{minutes:02}:{seconds:02}.{milliseconds:03}"
+    ))
+}
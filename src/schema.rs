@@ -0,0 +1,125 @@
+/// [JSON Schema](https://json-schema.org) of a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md),
+/// following the fields and types given in the specification.
+const JSON_CHAPTERS_SCHEMA: &str = r##"{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "JSON chapters file",
+  "type": "object",
+  "required": ["version", "chapters"],
+  "properties": {
+    "version": { "type": "string" },
+    "author": { "type": "string" },
+    "title": { "type": "string" },
+    "podcastName": { "type": "string" },
+    "description": { "type": "string" },
+    "fileName": { "type": "string" },
+    "waypoints": { "type": "boolean" },
+    "chapters": {
+      "type": "array",
+      "items": { "$ref": "#/definitions/chapter" }
+    }
+  },
+  "definitions": {
+    "chapter": {
+      "type": "object",
+      "required": ["startTime"],
+      "properties": {
+        "startTime": { "type": "number", "minimum": 0 },
+        "endTime": { "type": "number", "minimum": 0 },
+        "title": { "type": "string" },
+        "language": { "type": "string" },
+        "img": { "type": "string" },
+        "url": { "type": "string" },
+        "toc": { "type": "boolean" },
+        "location": { "$ref": "#/definitions/location" }
+      }
+    },
+    "location": {
+      "type": "object",
+      "required": ["name"],
+      "properties": {
+        "name": { "type": "string" },
+        "geo": { "type": "string" },
+        "osm": { "type": "string" }
+      }
+    }
+  }
+}"##;
+
+/// Checks a [JSON chapters file](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md)
+/// against a bundled [JSON Schema](https://json-schema.org) of the specification.
+///
+/// This is stricter than [from_json](crate::from_json), which, e.g., ignores fields of the wrong
+/// type where it can. Every violation is reported as a message prefixed with the path of the
+/// offending value, e.g., `chapters[2].startTime: "abc" is not of type "number"`. Requires the
+/// `schema` feature.
+///
+/// # Example:
+/// ```rust
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let json = r#"{
+///   "version": "1.2.0",
+///   "chapters": [
+///     {
+///       "startTime": 0,
+///       "title": "Chapter 1"
+///     },
+///     {
+///       "startTime": "30",
+///       "toc": "no"
+///     }
+///   ]
+/// }"#;
+///
+/// assert_eq!(
+///     chapters::validate_json_against_schema(json.as_bytes()),
+///     Err(vec![
+///         r#"chapters[1].startTime: "30" is not of type "number""#.to_string(),
+///         r#"chapters[1].toc: "no" is not of type "boolean""#.to_string(),
+///     ])
+/// );
+/// # }
+/// ```
+pub fn validate_json_against_schema<R: std::io::Read>(reader: R) -> Result<(), Vec<String>> {
+    let bytes = crate::json::read_without_bom(reader).map_err(|e| vec![e])?;
+    let json: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| vec![e.to_string()])?;
+
+    let schema: serde_json::Value =
+        serde_json::from_str(JSON_CHAPTERS_SCHEMA).map_err(|e| vec![e.to_string()])?;
+    let validator = jsonschema::validator_for(&schema).map_err(|e| vec![e.to_string()])?;
+
+    let errors: Vec<String> = validator
+        .iter_errors(&json)
+        .map(|error| match value_path(error.instance_path.as_str()) {
+            path if path.is_empty() => error.to_string(),
+            path => format!("{}: {}", path, error),
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Converts a JSON pointer, e.g., "/chapters/2/startTime", to a path like "chapters[2].startTime".
+fn value_path(pointer: &str) -> String {
+    let mut path = String::new();
+
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.parse::<usize>().is_ok() {
+            path.push_str(&format!("[{}]", segment));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(&segment);
+        }
+    }
+
+    path
+}
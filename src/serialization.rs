@@ -1,5 +1,6 @@
 use crate::Image;
 use chrono::Duration;
+use serde::ser::SerializeStructVariant;
 use serde::{Deserialize, Serialize};
 
 // Serialize impl
@@ -9,8 +10,40 @@ impl Serialize for Image {
         S: serde::Serializer,
     {
         match self {
-            Image::Url(url) => {
-                serializer.serialize_newtype_variant("image", 0, "Url", url.as_str())
+            Image::Url {
+                url,
+                mime_type: None,
+                width: None,
+                height: None,
+            } => serializer.serialize_newtype_variant("image", 0, "Url", url.as_str()),
+            Image::Url {
+                url,
+                mime_type,
+                width,
+                height,
+            } => {
+                let len = 1
+                    + mime_type.is_some() as usize
+                    + width.is_some() as usize
+                    + height.is_some() as usize;
+                let mut state = serializer.serialize_struct_variant("image", 0, "Url", len)?;
+                state.serialize_field("url", url.as_str())?;
+                if let Some(mime_type) = mime_type {
+                    state.serialize_field("mime_type", mime_type)?;
+                }
+                if let Some(width) = width {
+                    state.serialize_field("width", width)?;
+                }
+                if let Some(height) = height {
+                    state.serialize_field("height", height)?;
+                }
+                state.end()
+            }
+            Image::Data { mime_type, data } => {
+                let mut state = serializer.serialize_struct_variant("image", 1, "Data", 2)?;
+                state.serialize_field("mime_type", mime_type)?;
+                state.serialize_field("data", data)?;
+                state.end()
             }
         }
     }
@@ -20,19 +53,78 @@ pub fn float_to_duration_option<'de, D>(deserializer: D) -> Result<Option<Durati
 where
     D: serde::Deserializer<'de>,
 {
-    let f = match Option::<f64>::deserialize(deserializer) {
-        Ok(f) => f,
-        Err(_) => return Ok(None),
-    };
-    Ok(f.map(|f| Duration::milliseconds((f * 1000.0) as i64)))
+    // Only a null value means no time; anything else must be a valid time.
+    Option::<Seconds>::deserialize(deserializer)?
+        .map(|Seconds(f)| seconds_to_duration(f))
+        .transpose()
+        .map_err(serde::de::Error::custom)
 }
 
 pub fn float_to_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let f = f64::deserialize(deserializer)?;
-    Ok(Duration::milliseconds((f * 1000.0) as i64))
+    let Seconds(f) = Seconds::deserialize(deserializer)?;
+    seconds_to_duration(f).map_err(serde::de::Error::custom)
+}
+
+/// Number of seconds given either as a number or, as some non-conformant tools write it, as a
+/// numeric string, e.g., `"30.5"`.
+struct Seconds(f64);
+
+impl<'de> Deserialize<'de> for Seconds {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SecondsVisitor;
+
+        impl serde::de::Visitor<'_> for SecondsVisitor {
+            type Value = Seconds;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a number of seconds")
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(Seconds(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(Seconds(v as f64))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(Seconds(v as f64))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.trim().parse().map(Seconds).map_err(|_| {
+                    E::custom(format!(
+                        "invalid time `{}`, expected a number of seconds",
+                        v
+                    ))
+                })
+            }
+        }
+
+        deserializer.deserialize_any(SecondsVisitor)
+    }
+}
+
+/// Converts seconds to a duration, rejecting negative, infinite, NaN, and too large values, which
+/// would otherwise be silently turned into nonsensical times.
+fn seconds_to_duration(seconds: f64) -> Result<Duration, String> {
+    // Largest number of seconds whose milliseconds fit into an i64.
+    const MAX_SECONDS: f64 = i64::MAX as f64 / 1000.0;
+
+    if !(0.0..MAX_SECONDS).contains(&seconds) {
+        return Err(format!(
+            "invalid time {:?}, expected a non-negative number of seconds",
+            seconds
+        ));
+    }
+    Ok(Duration::milliseconds((seconds * 1000.0).round() as i64))
 }
 
 pub fn duration_option_to_float_option<S>(
@@ -84,3 +176,173 @@ where
         None => serializer.serialize_none(),
     }
 }
+
+/// Reads a list of URLs, skipping the invalid ones.
+#[cfg(feature = "rssblue")]
+pub fn strings_to_urls<'de, D>(deserializer: D) -> Result<Vec<url::Url>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let strings = Vec::<String>::deserialize(deserializer)?;
+    Ok(strings
+        .iter()
+        .filter_map(|s| url::Url::parse(s).ok())
+        .collect())
+}
+
+#[cfg(feature = "rssblue")]
+pub fn urls_to_strings<S>(urls: &[url::Url], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_seq(urls.iter().map(|url| url.as_str()))
+}
+
+/// Pretty-prints JSON chapters like [serde_json::ser::PrettyFormatter], but writes times, i.e.,
+/// the values of `startTime` and `endTime` keys, with a fixed number of decimals, e.g., `30.500`.
+/// Other numbers, such as `imgWidth`, are written as usual.
+pub struct FixedDecimalsFormatter {
+    pretty: serde_json::ser::PrettyFormatter<'static>,
+    decimals: usize,
+    /// Whether an object key is being written.
+    in_key: bool,
+    /// The most recently written object key.
+    key: String,
+}
+
+impl FixedDecimalsFormatter {
+    pub fn new(decimals: u8) -> Self {
+        Self {
+            pretty: serde_json::ser::PrettyFormatter::new(),
+            decimals: decimals as usize,
+            in_key: false,
+            key: String::new(),
+        }
+    }
+
+    fn is_time(&self) -> bool {
+        !self.in_key && matches!(self.key.as_str(), "startTime" | "endTime")
+    }
+
+    fn write_time<W>(&self, writer: &mut W, value: f64) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        write!(writer, "{:.*}", self.decimals, value)
+    }
+}
+
+impl serde_json::ser::Formatter for FixedDecimalsFormatter {
+    fn write_i64<W>(&mut self, writer: &mut W, value: i64) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        if self.is_time() {
+            return self.write_time(writer, value as f64);
+        }
+        self.pretty.write_i64(writer, value)
+    }
+
+    fn write_u64<W>(&mut self, writer: &mut W, value: u64) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        if self.is_time() {
+            return self.write_time(writer, value as f64);
+        }
+        self.pretty.write_u64(writer, value)
+    }
+
+    fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        if self.is_time() {
+            return self.write_time(writer, value);
+        }
+        self.pretty.write_f64(writer, value)
+    }
+
+    fn write_string_fragment<W>(&mut self, writer: &mut W, fragment: &str) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        if self.in_key {
+            self.key.push_str(fragment);
+        }
+        self.pretty.write_string_fragment(writer, fragment)
+    }
+
+    fn begin_array<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.begin_array(writer)
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.end_array(writer)
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.end_array_value(writer)
+    }
+
+    fn begin_object<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.begin_object(writer)
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.end_object(writer)
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.in_key = true;
+        self.key.clear();
+        self.pretty.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.in_key = false;
+        self.pretty.end_object_key(writer)
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.begin_object_value(writer)
+    }
+
+    fn end_object_value<W>(&mut self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        self.pretty.end_object_value(writer)
+    }
+}
@@ -0,0 +1,191 @@
+use crate::Chapter;
+use chrono::Duration;
+
+/// Reads [chapters](crate::Chapter) from an [SRT subtitle file](https://en.wikipedia.org/wiki/SubRip).
+///
+/// Every subtitle block (cue) becomes a chapter: the `HH:MM:SS,mmm --> HH:MM:SS,mmm` timing line
+/// gives the start and end times, and the subtitle text is used as the title, with multiple lines
+/// joined by a space. Blocks are separated by blank lines, and their sequence numbers are ignored.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let srt = "1
+/// 00:00:00,000 --> 00:05:04,800
+/// Intro
+///
+/// 2
+/// 00:05:04,800 --> 01:12:15,000
+/// Baboons and
+/// Steve Jobs
+/// ";
+///
+/// let chapters = chapters::from_srt(srt.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800),
+///             end: Some(Duration::hours(1) + Duration::minutes(12) + Duration::seconds(15)),
+///             title: Some("Baboons and Steve Jobs".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_srt<R: std::io::Read>(mut reader: R) -> Result<Vec<Chapter>, String> {
+    let mut srt = String::new();
+    reader
+        .read_to_string(&mut srt)
+        .map_err(|e| format!("Error reading SRT file: {}", e))?;
+
+    read_cues(&srt, |_| false)
+}
+
+/// Reads [chapters](crate::Chapter) from a [WebVTT file](https://www.w3.org/TR/webvtt1/).
+///
+/// Cues are read like in [from_srt](crate::from_srt), except that timestamps use a period before
+/// the milliseconds and may leave out the hours, e.g., `05:04.800`. The `WEBVTT` header as well
+/// as `NOTE`, `STYLE`, and `REGION` blocks are skipped, and cue settings after the end time are
+/// ignored.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let vtt = "WEBVTT
+///
+/// NOTE Chapters of episode 42
+///
+/// intro
+/// 00:00.000 --> 05:04.800
+/// Intro
+///
+/// 05:04.800 --> 1:12:15.000 line:0
+/// Baboons and
+/// Steve Jobs
+/// ";
+///
+/// let chapters = chapters::from_webvtt(vtt.as_bytes()).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800)),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800),
+///             end: Some(Duration::hours(1) + Duration::minutes(12) + Duration::seconds(15)),
+///             title: Some("Baboons and Steve Jobs".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_webvtt<R: std::io::Read>(mut reader: R) -> Result<Vec<Chapter>, String> {
+    let mut vtt = String::new();
+    reader
+        .read_to_string(&mut vtt)
+        .map_err(|e| format!("Error reading WebVTT file: {}", e))?;
+
+    if !vtt.trim_start_matches('\u{feff}').starts_with("WEBVTT") {
+        return Err(String::from("WebVTT file does not start with `WEBVTT`"));
+    }
+
+    read_cues(&vtt, |block| {
+        ["WEBVTT", "NOTE", "STYLE", "REGION"]
+            .iter()
+            .any(|keyword| block[0].starts_with(keyword) && !block[0].contains("-->"))
+    })
+}
+
+/// Reads every block of subtitle cues as a chapter, skipping blocks for which `skip` is true.
+fn read_cues(text: &str, skip: impl Fn(&[&str]) -> bool) -> Result<Vec<Chapter>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+
+    let mut chapters = Vec::new();
+    let mut lines = text.lines().map(|line| line.trim()).peekable();
+
+    while lines.peek().is_some() {
+        // Everything up to the next blank line is a single block.
+        let block: Vec<&str> = lines.by_ref().take_while(|line| !line.is_empty()).collect();
+        if block.is_empty() || skip(&block) {
+            continue;
+        }
+
+        let timing_index = block
+            .iter()
+            .position(|line| line.contains("-->"))
+            .ok_or_else(|| format!("Subtitle block `{}` has no timing line", block[0]))?;
+        let (start, end) = block[timing_index].split_once("-->").unwrap();
+        // The end time may be followed by display coordinates or cue settings.
+        let end = end.split_whitespace().next().unwrap_or_default();
+
+        let title = block[timing_index + 1..].join(" ");
+
+        chapters.push(Chapter {
+            start: parse_srt_timestamp(start.trim())?,
+            end: Some(parse_srt_timestamp(end)?),
+            title: if title.is_empty() { None } else { Some(title) },
+            ..Default::default()
+        });
+    }
+
+    Ok(chapters)
+}
+
+/// Parses an SRT timestamp in `HH:MM:SS,mmm` format. The hours may be left out, as in WebVTT.
+fn parse_srt_timestamp(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid SRT timestamp `{}`", s);
+
+    // Some tools use a period rather than a comma before the milliseconds.
+    let (time, milliseconds) = s.split_once([',', '.']).ok_or_else(invalid)?;
+    let parts = time
+        .split(':')
+        .chain([milliseconds])
+        .map(|part| {
+            // Unlike `i64::from_str`, SRT doesn't allow a sign.
+            if !part.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            part.parse::<i64>().map_err(|_| invalid())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let parts = match parts[..] {
+        [minutes, seconds, milliseconds] => [0, minutes, seconds, milliseconds],
+        [hours, minutes, seconds, milliseconds] => [hours, minutes, seconds, milliseconds],
+        _ => return Err(invalid()),
+    };
+    match parts {
+        [hours, minutes, seconds, milliseconds]
+            if minutes < 60 && seconds < 60 && milliseconds < 1000 =>
+        {
+            Duration::try_hours(hours)
+                .and_then(|total| total.checked_add(&Duration::try_minutes(minutes)?))
+                .and_then(|total| total.checked_add(&Duration::try_seconds(seconds)?))
+                .and_then(|total| total.checked_add(&Duration::try_milliseconds(milliseconds)?))
+                .ok_or_else(invalid)
+        }
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{serialization, Chapter, Image, Link};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Chapters file in TOML, with one `[[chapters]]` table per chapter.
+#[derive(Debug, Deserialize, Serialize)]
+struct TomlChapters {
+    #[serde(default)]
+    chapters: Vec<TomlChapter>,
+}
+
+/// Individual `[[chapters]]` table inside [TomlChapters].
+#[derive(Debug, Deserialize, Serialize)]
+struct TomlChapter {
+    #[serde(
+        deserialize_with = "serialization::float_to_duration",
+        serialize_with = "serialization::duration_to_float"
+    )]
+    start: Duration,
+    #[serde(
+        default,
+        deserialize_with = "serialization::float_to_duration_option",
+        serialize_with = "serialization::duration_option_to_float_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    end: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "serialization::string_to_url",
+        serialize_with = "serialization::url_option_to_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    url: Option<url::Url>,
+    #[serde(
+        default,
+        deserialize_with = "serialization::string_to_url",
+        serialize_with = "serialization::url_option_to_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    img: Option<url::Url>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    hidden: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl From<TomlChapter> for Chapter {
+    fn from(toml_chapter: TomlChapter) -> Self {
+        Self {
+            start: toml_chapter.start,
+            end: toml_chapter.end,
+            title: toml_chapter.title,
+            link: toml_chapter.url.map(|url| Link { url, title: None }),
+            image: toml_chapter.img.map(Image::from),
+            hidden: toml_chapter.hidden,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Chapter> for TomlChapter {
+    fn from(chapter: &Chapter) -> Self {
+        Self {
+            start: chapter.start,
+            end: chapter.end,
+            title: chapter.title.clone(),
+            url: chapter.link.as_ref().map(|link| link.url.clone()),
+            img: match &chapter.image {
+                Some(Image::Url { url, .. }) => Some(url.clone()),
+                _ => None,
+            },
+            hidden: chapter.hidden,
+        }
+    }
+}
+
+/// Reads [chapters](crate::Chapter) from a TOML document with one `[[chapters]]` table per chapter.
+///
+/// Each table has a `start` time and optionally an `end` time, both in seconds (integers or
+/// floats, like in [JSON](crate::from_json)), as well as optional `title`, `url` (the chapter's
+/// [link](crate::Chapter::link)), `img` (the chapter's [image](crate::Chapter::image)), and
+/// `hidden` keys. Missing keys are read as `None` (or `false` for `hidden`), and invalid URLs
+/// are ignored. Requires the `toml` feature.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let toml = r#"
+/// [[chapters]]
+/// start = 0
+/// end = 30.5
+/// title = "Intro"
+/// url = "https://example.com/intro"
+///
+/// [[chapters]]
+/// start = 30.5
+/// title = "Sponsor"
+/// hidden = true
+/// "#;
+///
+/// let chapters = chapters::from_toml(toml).expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             end: Some(Duration::milliseconds(30_500)),
+///             title: Some("Intro".to_string()),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com/intro").unwrap(),
+///                 title: None,
+///             }),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::milliseconds(30_500),
+///             title: Some("Sponsor".to_string()),
+///             hidden: true,
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_toml(toml: &str) -> Result<Vec<Chapter>, String> {
+    let toml_chapters: TomlChapters =
+        ::toml::from_str(toml).map_err(|e| format!("Error parsing TOML: {}", e))?;
+
+    Ok(toml_chapters
+        .chapters
+        .into_iter()
+        .map(Chapter::from)
+        .collect())
+}
+
+/// Writes [chapters](crate::Chapter) to a TOML document with one `[[chapters]]` table per chapter.
+///
+/// The keys are the same as in [from_toml], with times written as seconds like in
+/// [JSON](crate::to_json). Fields that have no TOML key, such as link titles or embedded images,
+/// are left out. Requires the `toml` feature.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::Chapter;
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         end: Some(Duration::milliseconds(30_500)),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::milliseconds(30_500),
+///         title: Some("Sponsor".to_string()),
+///         hidden: true,
+///         ..Default::default()
+///     },
+/// ];
+///
+/// let toml = chapters::to_toml(&chapters).expect("Failed to write chapters");
+///
+/// assert_eq!(
+///     toml,
+///     r#"[[chapters]]
+/// start = 0
+/// end = 30.5
+/// title = "Intro"
+///
+/// [[chapters]]
+/// start = 30.5
+/// title = "Sponsor"
+/// hidden = true
+/// "#
+/// );
+/// assert_eq!(chapters::from_toml(&toml), Ok(chapters));
+/// # }
+/// ```
+pub fn to_toml(chapters: &[Chapter]) -> Result<String, String> {
+    let toml_chapters = TomlChapters {
+        chapters: chapters.iter().map(TomlChapter::from).collect(),
+    };
+
+    ::toml::to_string(&toml_chapters).map_err(|e| format!("Error writing TOML: {}", e))
+}
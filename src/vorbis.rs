@@ -0,0 +1,430 @@
+use crate::{Chapter, Link};
+use chrono::Duration;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Type of the FLAC metadata block holding Vorbis comments.
+const FLAC_VORBIS_COMMENT: u8 = 4;
+
+/// Reads [chapters](crate::Chapter) from an Ogg Opus file's Vorbis comments.
+///
+/// Chapters follow the [Vorbis comment chapter
+/// extension](https://wiki.xiph.org/Chapter_Extension): `CHAPTERnnn` holds the start time in
+/// `HH:MM:SS.mmm` format, `CHAPTERnnnNAME` the title, and `CHAPTERnnnURL` a link. Chapters are
+/// sorted by their index `nnn`, and comment names are matched case-insensitively.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// # use pretty_assertions::assert_eq;
+/// #
+/// # fn main() {
+/// let chapters = chapters::from_opus_file("tests/data/vorbis-comment-chapters.opus")
+///     .expect("Failed to parse chapters");
+///
+/// assert_eq!(
+///     chapters,
+///     vec![
+///         Chapter {
+///             start: Duration::zero(),
+///             title: Some("Intro".to_string()),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800),
+///             title: Some("Baboons".to_string()),
+///             link: Some(Link {
+///                 url: url::Url::parse("https://example.com/baboons").unwrap(),
+///                 title: None,
+///             }),
+///             ..Default::default()
+///         },
+///         Chapter {
+///             start: Duration::hours(1) + Duration::minutes(12) + Duration::seconds(15),
+///             title: Some("Steve Jobs — 日本".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+/// # }
+/// ```
+pub fn from_opus_file<P: AsRef<Path>>(path: P) -> Result<Vec<Chapter>, String> {
+    let error = |e: String| {
+        format!(
+            "Error reading Opus file `{}`: {}",
+            path.as_ref().display(),
+            e
+        )
+    };
+
+    let file = std::fs::File::open(&path).map_err(|e| error(e.to_string()))?;
+    let packet = read_ogg_packet(std::io::BufReader::new(file), 1).map_err(error)?;
+    let comments = packet
+        .strip_prefix(b"OpusTags")
+        .ok_or_else(|| error(String::from("missing `OpusTags` header")))?;
+
+    let (_, comments) = parse_comments(comments).map_err(error)?;
+    chapters_from_comments(&comments)
+}
+
+/// Writes [chapters](crate::Chapter) to a FLAC file's Vorbis comments.
+///
+/// The source file is copied to `dst_path` with `CHAPTERnnn` (the start time in `HH:MM:SS.mmm`
+/// format), `CHAPTERnnnNAME` (the title), and `CHAPTERnnnURL` (the link) comments, as described
+/// by the [Vorbis comment chapter extension](https://wiki.xiph.org/Chapter_Extension). The index
+/// `nnn` is zero-padded to three digits, so at most 999 chapters can be written. Existing chapter
+/// comments are replaced, while all other comments are kept.
+///
+/// # Example:
+/// ```rust
+/// # use chapters::{Chapter, Link};
+/// # use chrono::Duration;
+/// #
+/// # fn main() {
+/// #     let src_filepath = std::path::Path::new("tests/data/vorbis-comment-chapters.flac");
+/// #     let dst_filepath = std::path::Path::new("tests/data/vorbis-comment-chapters.chapters-added.flac");
+/// #
+/// let chapters = vec![
+///     Chapter {
+///         start: Duration::zero(),
+///         title: Some("Intro".to_string()),
+///         ..Default::default()
+///     },
+///     Chapter {
+///         start: Duration::minutes(5) + Duration::seconds(4) + Duration::milliseconds(800),
+///         title: Some("Baboons".to_string()),
+///         link: Some(Link {
+///             url: url::Url::parse("https://example.com/baboons").unwrap(),
+///             title: None,
+///         }),
+///         ..Default::default()
+///     },
+/// ];
+///
+/// chapters::to_flac_file(src_filepath, dst_filepath, &chapters).expect("Failed to write chapters");
+/// #
+/// # let flac = std::fs::read(dst_filepath).unwrap();
+/// # let contains = |needle: &[u8]| flac.windows(needle.len()).any(|window| window == needle);
+/// # assert!(contains(b"CHAPTER002=00:05:04.800"));
+/// # assert!(contains(b"CHAPTER002URL=https://example.com/baboons"));
+/// # assert!(!contains(b"Old chapter"));
+/// # assert!(contains(b"TITLE=Test episode"));
+/// # assert!(flac.ends_with(b"\xff\xf8AUDIO-FRAMES"));
+/// #
+/// # // Cleanup
+/// # std::fs::remove_file(dst_filepath).unwrap();
+/// # }
+/// ```
+pub fn to_flac_file<P: AsRef<Path>>(
+    src_path: P,
+    dst_path: P,
+    chapters: &[Chapter],
+) -> Result<(), String> {
+    if chapters.len() > 999 {
+        return Err(format!(
+            "Vorbis comments can have at most 999 chapters, but there are {}",
+            chapters.len()
+        ));
+    }
+
+    let read_error = |e: String| {
+        format!(
+            "Error reading FLAC file `{}`: {}",
+            src_path.as_ref().display(),
+            e
+        )
+    };
+    let mut src = std::io::BufReader::new(
+        std::fs::File::open(&src_path).map_err(|e| read_error(e.to_string()))?,
+    );
+    let mut blocks = read_flac_metadata(&mut src).map_err(read_error)?;
+
+    let (vendor, mut comments) = match blocks
+        .iter()
+        .position(|(block_type, _)| *block_type == FLAC_VORBIS_COMMENT)
+    {
+        Some(i) => parse_comments(&blocks.remove(i).1).map_err(read_error)?,
+        None => (String::from("chapters"), Vec::new()),
+    };
+    comments.retain(|comment| !is_chapter_comment(comment));
+    for (i, chapter) in chapters.iter().enumerate() {
+        comments.push(format!(
+            "CHAPTER{:03}={}",
+            i + 1,
+            format_chapter_timestamp(chapter.start)?
+        ));
+        if let Some(title) = &chapter.title {
+            comments.push(format!("CHAPTER{:03}NAME={}", i + 1, title));
+        }
+        if let Some(link) = &chapter.link {
+            comments.push(format!("CHAPTER{:03}URL={}", i + 1, link.url));
+        }
+    }
+    // The comments go right after the mandatory `STREAMINFO` block.
+    blocks.insert(
+        1.min(blocks.len()),
+        (FLAC_VORBIS_COMMENT, encode_comments(&vendor, &comments)),
+    );
+
+    // Write to a temporary file first so that the source can also be the destination.
+    let tmp_path = {
+        let mut file_name = dst_path
+            .as_ref()
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string();
+        file_name.push(".tmp");
+        dst_path.as_ref().with_file_name(file_name)
+    };
+    let write_error = |e: String| {
+        format!(
+            "Error writing FLAC file `{}`: {}",
+            dst_path.as_ref().display(),
+            e
+        )
+    };
+    let mut dst = std::io::BufWriter::new(
+        std::fs::File::create(&tmp_path).map_err(|e| write_error(e.to_string()))?,
+    );
+
+    dst.write_all(b"fLaC")
+        .map_err(|e| write_error(e.to_string()))?;
+    for (i, (block_type, data)) in blocks.iter().enumerate() {
+        if data.len() >= 1 << 24 {
+            return Err(write_error(String::from("metadata block is too large")));
+        }
+        let last = if i == blocks.len() - 1 { 0x80 } else { 0 };
+        dst.write_all(&[last | block_type])
+            .and_then(|_| dst.write_all(&(data.len() as u32).to_be_bytes()[1..]))
+            .and_then(|_| dst.write_all(data))
+            .map_err(|e| write_error(e.to_string()))?;
+    }
+    // The audio frames follow the metadata unchanged.
+    std::io::copy(&mut src, &mut dst).map_err(|e| write_error(e.to_string()))?;
+    dst.flush().map_err(|e| write_error(e.to_string()))?;
+    drop(dst);
+
+    std::fs::rename(&tmp_path, &dst_path).map_err(|e| write_error(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Reads the metadata blocks of a FLAC file as (type, data) pairs, leaving `reader` at the first
+/// audio frame.
+fn read_flac_metadata<R: Read>(reader: &mut R) -> Result<Vec<(u8, Vec<u8>)>, String> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic).map_err(|e| e.to_string())?;
+    if &magic != b"fLaC" {
+        return Err(String::from("missing `fLaC` marker"));
+    }
+
+    let mut blocks = Vec::new();
+    loop {
+        let mut header = [0; 4];
+        reader.read_exact(&mut header).map_err(|e| e.to_string())?;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+        let mut data = vec![0; len as usize];
+        reader.read_exact(&mut data).map_err(|e| e.to_string())?;
+        blocks.push((header[0] & 0x7f, data));
+
+        if header[0] & 0x80 != 0 {
+            return Ok(blocks);
+        }
+    }
+}
+
+/// Checks whether a comment is one of the `CHAPTERnnn...` comments.
+fn is_chapter_comment(comment: &str) -> bool {
+    let name = comment.split('=').next().unwrap_or_default();
+    name.get(..7)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("CHAPTER"))
+        && name[7..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Reads the packet with the given index from the first logical stream of an Ogg file.
+fn read_ogg_packet<R: Read>(mut reader: R, index: usize) -> Result<Vec<u8>, String> {
+    let mut serial = None;
+    let mut packet = Vec::new();
+    let mut packet_index = 0;
+
+    loop {
+        let mut header = [0; 27];
+        reader.read_exact(&mut header).map_err(|e| e.to_string())?;
+        if &header[..4] != b"OggS" {
+            return Err(String::from("invalid Ogg page"));
+        }
+        let page_serial = u32::from_le_bytes(header[14..18].try_into().unwrap());
+        let mut segment_table = vec![0; header[26] as usize];
+        reader
+            .read_exact(&mut segment_table)
+            .map_err(|e| e.to_string())?;
+        let mut data = vec![0; segment_table.iter().map(|len| *len as usize).sum()];
+        reader.read_exact(&mut data).map_err(|e| e.to_string())?;
+
+        // Skip pages of other logical streams.
+        if *serial.get_or_insert(page_serial) != page_serial {
+            continue;
+        }
+
+        // A packet ends with the first segment shorter than 255 bytes.
+        let mut offset = 0;
+        for len in segment_table {
+            let segment = &data[offset..offset + len as usize];
+            offset += len as usize;
+            if packet_index == index {
+                packet.extend_from_slice(segment);
+            }
+            if len < 255 {
+                if packet_index == index {
+                    return Ok(packet);
+                }
+                packet_index += 1;
+            }
+        }
+    }
+}
+
+/// Parses a Vorbis comment header (without its packet type prefix) into the vendor string and the
+/// `NAME=value` comments.
+fn parse_comments(data: &[u8]) -> Result<(String, Vec<String>), String> {
+    let mut offset = 0;
+
+    let vendor = read_string(data, &mut offset)?;
+    let count = read_u32(data, &mut offset)?;
+    let comments = (0..count)
+        .map(|_| read_string(data, &mut offset))
+        .collect::<Result<_, _>>()?;
+
+    Ok((vendor, comments))
+}
+
+/// Encodes a Vorbis comment header (without its packet type prefix or framing bit).
+fn encode_comments(vendor: &str, comments: &[String]) -> Vec<u8> {
+    let push_string = |data: &mut Vec<u8>, string: &str| {
+        data.extend_from_slice(&(string.len() as u32).to_le_bytes());
+        data.extend_from_slice(string.as_bytes());
+    };
+
+    let mut data = Vec::new();
+    push_string(&mut data, vendor);
+    data.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+    for comment in comments {
+        push_string(&mut data, comment);
+    }
+
+    data
+}
+
+/// Reads a little-endian 32-bit integer and advances `offset` past it.
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32, String> {
+    let value = data
+        .get(*offset..*offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| String::from("unexpected end of comment header"))?;
+    *offset += 4;
+    Ok(value)
+}
+
+/// Reads a length-prefixed UTF-8 string and advances `offset` past it.
+fn read_string(data: &[u8], offset: &mut usize) -> Result<String, String> {
+    let len = read_u32(data, offset)? as usize;
+    let string = data
+        .get(*offset..*offset + len)
+        .ok_or_else(|| String::from("unexpected end of comment header"))?;
+    *offset += len;
+    Ok(String::from_utf8_lossy(string).into_owned())
+}
+
+/// The comments describing a single chapter.
+#[derive(Default)]
+struct ChapterComments {
+    start: Option<Duration>,
+    title: Option<String>,
+    url: Option<url::Url>,
+}
+
+/// Collects chapters from `CHAPTERnnn`, `CHAPTERnnnNAME`, and `CHAPTERnnnURL` comments.
+fn chapters_from_comments(comments: &[String]) -> Result<Vec<Chapter>, String> {
+    let mut chapters: BTreeMap<u32, ChapterComments> = BTreeMap::new();
+
+    for comment in comments {
+        let Some((name, value)) = comment.split_once('=') else {
+            continue;
+        };
+        let name = name.to_uppercase();
+        let Some(rest) = name.strip_prefix("CHAPTER") else {
+            continue;
+        };
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let Ok(index) = rest[..digits].parse::<u32>() else {
+            continue;
+        };
+
+        let chapter = chapters.entry(index).or_default();
+        match &rest[digits..] {
+            "" => chapter.start = Some(parse_chapter_timestamp(value)?),
+            "NAME" => chapter.title = Some(value.to_string()),
+            "URL" => chapter.url = Some(url::Url::parse(value).map_err(|e| e.to_string())?),
+            _ => {}
+        }
+    }
+
+    chapters
+        .into_iter()
+        .map(|(index, chapter)| {
+            Ok(Chapter {
+                start: chapter
+                    .start
+                    .ok_or_else(|| format!("Chapter {:03} is missing a start", index))?,
+                title: chapter.title,
+                link: chapter.url.map(|url| Link { url, title: None }),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses a chapter timestamp in `HH:MM:SS.mmm` format.
+fn parse_chapter_timestamp(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid chapter timestamp `{}`", s);
+
+    let (time, fraction) = s.split_once('.').unwrap_or((s, "0"));
+    let parts = time
+        .split(':')
+        .map(|part| part.parse::<i64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let milliseconds = format!("{:0<3}", fraction).parse::<i64>().unwrap();
+
+    match parts[..] {
+        [hours, minutes, seconds]
+            if hours >= 0 && (0..60).contains(&minutes) && (0..60).contains(&seconds) =>
+        {
+            Duration::try_hours(hours)
+                .and_then(|total| total.checked_add(&Duration::minutes(minutes)))
+                .and_then(|total| total.checked_add(&Duration::seconds(seconds)))
+                .and_then(|total| total.checked_add(&Duration::milliseconds(milliseconds)))
+                .ok_or_else(invalid)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Formats a chapter timestamp in `HH:MM:SS.mmm` format.
+fn format_chapter_timestamp(duration: Duration) -> Result<String, String> {
+    if duration < Duration::zero() {
+        return Err(format!("Chapter start `{}` is negative", duration));
+    }
+    let milliseconds = duration.num_milliseconds();
+    Ok(format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        milliseconds / 3_600_000,
+        milliseconds / 60_000 % 60,
+        milliseconds / 1000 % 60,
+        milliseconds % 1000
+    ))
+}
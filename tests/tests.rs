@@ -1,9 +1,13 @@
+#[cfg(feature = "json")]
 use cfg_if::cfg_if;
 #[cfg(feature = "rssblue")]
 use chapters::RemoteEntity;
-use chapters::{from_json, Chapter, Image, Link};
+#[cfg(feature = "json")]
+use chapters::{from_json, Location};
+use chapters::{Chapter, Image, Link};
 use pretty_assertions::assert_eq;
 
+#[cfg(feature = "json")]
 #[test]
 fn test_json() {
     struct Test {
@@ -23,7 +27,7 @@ fn test_json() {
                 Chapter {
                     start: chrono::Duration::seconds(168),
                     title: Some(String::from("Hearing Aids")),
-                    image: Some(Image::Url(
+                    image: Some(Image::from(
                         url::Url::parse("https://example.com/images/hearing_aids.jpg").unwrap(),
                     )),
                     ..Default::default()
@@ -36,7 +40,7 @@ fn test_json() {
                 Chapter {
                     start: chrono::Duration::seconds(410),
                     title: Some(String::from("Namespace")),
-                    image: Some(Image::Url(
+                    image: Some(Image::from(
                         url::Url::parse("https://example.com/images/namepsace_example.jpg")
                             .unwrap(),
                     )),
@@ -52,7 +56,7 @@ fn test_json() {
                 Chapter {
                     start: chrono::Duration::seconds(3990),
                     title: Some(String::from("Just Break Up")),
-                    image: Some(Image::Url(
+                    image: Some(Image::from(
                         url::Url::parse("https://example.com/images/justbreakuppod.png").unwrap(),
                     )),
                     ..Default::default()
@@ -97,7 +101,7 @@ fn test_json() {
                 Chapter {
                     start: chrono::Duration::seconds(168),
                     title: Some(String::from("Hearing Aids")),
-                    image: Some(Image::Url(
+                    image: Some(Image::from(
                         url::Url::parse("https://example.com/images/hearing_aids.jpg").unwrap(),
                     )),
                     ..Default::default()
@@ -110,7 +114,7 @@ fn test_json() {
                 Chapter {
                     start: chrono::Duration::seconds(410),
                     title: Some(String::from("Namespace")),
-                    image: Some(Image::Url(
+                    image: Some(Image::from(
                         url::Url::parse("https://example.com/images/namepsace_example.jpg")
                             .unwrap(),
                     )),
@@ -119,14 +123,14 @@ fn test_json() {
                             "https://github.com/Podcastindex-org/podcast-namespace",
                         )
                         .unwrap(),
-                        title: None,
+                        title: Some(String::from("Podcast namespace")),
                     }),
                     ..Default::default()
                 },
                 Chapter {
                     start: chrono::Duration::seconds(3990),
                     title: Some(String::from("Just Break Up")),
-                    image: Some(Image::Url(
+                    image: Some(Image::from(
                         url::Url::parse("https://example.com/images/justbreakuppod.png").unwrap(),
                     )),
                     ..Default::default()
@@ -162,22 +166,82 @@ fn test_json() {
                 },
             ]),
         },
+        Test {
+            file_contents: include_str!("data/podcast-namespace-chapters.location.json"),
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Leaving home")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::seconds(95) + chrono::Duration::milliseconds(500),
+                    title: Some(String::from("Downtown")),
+                    location: Some(Location {
+                        name: String::from("Austin, TX"),
+                        geo: Some(String::from("geo:30.2672,97.7431")),
+                        osm: Some(String::from("R113314")),
+                    }),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::seconds(300),
+                    title: Some(String::from("Somewhere")),
+                    location: Some(Location {
+                        name: String::from("Unknown"),
+                        geo: None,
+                        osm: None,
+                    }),
+                    ..Default::default()
+                },
+            ]),
+        },
         Test {
             file_contents: include_str!("data/podcast-namespace-chapters.empty.json"),
             expected: Ok(vec![]),
         },
+        Test {
+            file_contents: include_str!("data/podcast-namespace-chapters.bom.json"),
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::seconds(30) + chrono::Duration::milliseconds(500),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
     ];
 
     for test in tests {
         if let Ok(expected) = test.expected.as_ref() {
             let serialized = chapters::to_json(expected).unwrap();
-            assert_eq!(serialized.trim(), test.file_contents.trim());
+            assert_eq!(
+                serialized.trim(),
+                test.file_contents.trim_start_matches('\u{feff}').trim()
+            );
+
+            // Compact output only differs in whitespace.
+            let compact = chapters::to_json_compact(expected).unwrap();
+            assert!(!compact.contains('\n'));
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+                serde_json::from_str::<serde_json::Value>(&serialized).unwrap()
+            );
         }
 
         let reader = std::io::BufReader::new(test.file_contents.as_bytes());
         let result = from_json(reader);
 
         assert_eq!(result, test.expected);
+
+        let streamed: Result<Vec<Chapter>, String> =
+            chapters::iter_json(test.file_contents.as_bytes()).collect();
+        assert_eq!(streamed, test.expected);
     }
 }
 
@@ -188,137 +252,2750 @@ fn test_from_description() {
         expected: Result<Vec<Chapter>, String>,
     }
 
-    let tests = vec![Test {
-        description: include_str!("data/description-chapters.txt"),
-        expected: Ok(vec![
-            Chapter {
-                start: chrono::Duration::seconds(0),
-                title: Some(String::from("Intro")),
-                ..Default::default()
-            },
-            Chapter {
-                start: chrono::Duration::minutes(4) + chrono::Duration::seconds(45),
-                title: Some(String::from("Plot summary")),
-                ..Default::default()
-            },
-            Chapter {
-                start: chrono::Duration::minutes(10) + chrono::Duration::seconds(11),
-                title: Some(String::from("Sergio Leone")),
-                ..Default::default()
-            },
-            Chapter {
-                start: chrono::Duration::minutes(16) + chrono::Duration::seconds(58),
-                title: Some(String::from("Ennio Morricone")),
-                ..Default::default()
-            },
-            Chapter {
-                start: chrono::Duration::minutes(22) + chrono::Duration::seconds(30),
-                title: Some(String::from("Charles Bronson")),
-                ..Default::default()
-            },
-            Chapter {
-                start: chrono::Duration::minutes(27) + chrono::Duration::seconds(22),
-                title: Some(String::from("Henry Fonda")),
-                ..Default::default()
-            },
-            Chapter {
-                start: chrono::Duration::minutes(32) + chrono::Duration::seconds(21),
-                title: Some(String::from("Conclusion")),
-                ..Default::default()
-            },
-        ]),
-    }];
-
-    for test in tests {
-        let result = chapters::from_description(test.description);
-
-        assert_eq!(result, test.expected);
-    }
-}
-
-#[test]
-fn test_to_json() {
-    let chapters = vec![
-        Chapter {
-            start: chrono::Duration::seconds(0),
-            end: Some(chrono::Duration::seconds(10) + chrono::Duration::milliseconds(400)),
-            title: Some(String::from("Start")),
-            link: Some(Link {
-                url: url::Url::parse("https://example.com").unwrap(),
-                title: Some(String::from("Example")),
-            }),
-            image: Some(Image::Url(
-                url::Url::parse("https://example.com/image.png").unwrap(),
-            )),
-            hidden: false,
-            #[cfg(feature = "rssblue")]
-            remote_entity: Some(RemoteEntity::Item {
-                feed_guid: uuid::Uuid::parse_str("917393e3-1b1e-5cef-ace4-edaa54e1f810").unwrap(),
-                guid: String::from("44a78abc-dffe-4de2-9230-6d6e723360a5"),
-            }),
+    let tests = vec![
+        Test {
+            description: include_str!("data/description-chapters.txt"),
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(4) + chrono::Duration::seconds(45),
+                    title: Some(String::from("Plot summary")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(10) + chrono::Duration::seconds(11),
+                    title: Some(String::from("Sergio Leone")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(16) + chrono::Duration::seconds(58),
+                    title: Some(String::from("Ennio Morricone")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(22) + chrono::Duration::seconds(30),
+                    title: Some(String::from("Charles Bronson")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(27) + chrono::Duration::seconds(22),
+                    title: Some(String::from("Henry Fonda")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(32) + chrono::Duration::seconds(21),
+                    title: Some(String::from("Conclusion")),
+                    ..Default::default()
+                },
+            ]),
         },
-        Chapter {
-            start: chrono::Duration::seconds(10) + chrono::Duration::milliseconds(400),
-            end: None,
-            title: None,
-            link: None,
-            image: None,
-            hidden: false,
-            #[cfg(feature = "rssblue")]
-            remote_entity: None,
+        Test {
+            description: include_str!("data/description-chapters.bom.txt"),
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("“The Movement”")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(9) + chrono::Duration::seconds(58),
+                    title: Some(String::from("Steve Jobs’ legacy")),
+                    ..Default::default()
+                },
+            ]),
         },
-    ];
+        Test {
+            description: "\u{feff}\n  \n\t\n00:00 Intro\n05:04 Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: r#"Welcome to the show!
 
-    // ensure indentation
-    let result = serde_json::to_string_pretty(&chapters).unwrap();
+00:00 Intro
+We start with some housekeeping.
+05:04 Baboons
 
-    cfg_if! { if #[cfg( feature = "rssblue" )]{
-    let expected = r#"[
-  {
-    "start": 0,
-    "end": 10.4,
-    "title": "Start",
-    "image": {
-      "Url": "https://example.com/image.png"
-    },
-    "link": {
-      "url": "https://example.com/",
-      "title": "Example"
-    },
-    "hidden": false,
-    "remote_entity": {
-      "item": {
-        "feed_guid": "917393e3-1b1e-5cef-ace4-edaa54e1f810",
-        "guid": "44a78abc-dffe-4de2-9230-6d6e723360a5"
-      }
-    }
-  },
-  {
-    "start": 10.4,
-    "hidden": false
-  }
-]"#;
-    } else {
-    let expected = r#"[
-  {
-    "start": 0,
-    "end": 10.4,
-    "title": "Start",
-    "image": {
-      "Url": "https://example.com/image.png"
-    },
-    "link": {
-      "url": "https://example.com/",
-      "title": "Example"
-    },
-    "hidden": false
-  },
-  {
-    "start": 10.4,
-    "hidden": false
-  }
-]"#;
-    }}
+Then we talk about Steve.
+09:58 Steve Jobs
 
-    assert_eq!(result, expected);
+Thanks for listening!"#,
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(9) + chrono::Duration::seconds(58),
+                    title: Some(String::from("Steve Jobs")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description:
+                "Intro 00:00\nBaboons - 05:04\nSteve Jobs - 1:02:03\nSteve Jobs, part 2 - 09:58\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(9) + chrono::Duration::seconds(58),
+                    title: Some(String::from("Steve Jobs, part 2")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "The Movement (00:00)\nBaboons (05:04)\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("The Movement")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "Recorded on 2023-10-01 at 10:30\n00:00 Intro\n05:04 Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "1:02:03 Segment one\n10:02:03 Segment two\n01:02:03 Segment three\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::hours(1)
+                        + chrono::Duration::minutes(2)
+                        + chrono::Duration::seconds(3),
+                    title: Some(String::from("Segment one")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(10)
+                        + chrono::Duration::minutes(2)
+                        + chrono::Duration::seconds(3),
+                    title: Some(String::from("Segment two")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(1)
+                        + chrono::Duration::minutes(2)
+                        + chrono::Duration::seconds(3),
+                    title: Some(String::from("Segment three")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description:
+                "00:00 Intro\n05:04.5 One digit\n05:10.25 Two digits\n05:16.125 Three digits\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5)
+                        + chrono::Duration::seconds(4)
+                        + chrono::Duration::milliseconds(500),
+                    title: Some(String::from("One digit")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5)
+                        + chrono::Duration::seconds(10)
+                        + chrono::Duration::milliseconds(250),
+                    title: Some(String::from("Two digits")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5)
+                        + chrono::Duration::seconds(16)
+                        + chrono::Duration::milliseconds(125),
+                    title: Some(String::from("Three digits")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "1:02:03.250 Three digits\n",
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::hours(1)
+                    + chrono::Duration::minutes(2)
+                    + chrono::Duration::seconds(3)
+                    + chrono::Duration::milliseconds(250),
+                title: Some(String::from("Three digits")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            description: "(05:04.125) Three digits\n",
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::minutes(5)
+                    + chrono::Duration::seconds(4)
+                    + chrono::Duration::milliseconds(125),
+                title: Some(String::from("Three digits")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            description: "(1:02:03) Segment one\n(10:02:03) Segment two\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::hours(1)
+                        + chrono::Duration::minutes(2)
+                        + chrono::Duration::seconds(3),
+                    title: Some(String::from("Segment one")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(10)
+                        + chrono::Duration::minutes(2)
+                        + chrono::Duration::seconds(3),
+                    title: Some(String::from("Segment two")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "00:00 – Intro\n05:04 – Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "00:00 — Intro\n05:04 — Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "00:00 : Intro\n05:04 : Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "00:00 | Intro\n05:04 | Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "Intro | 00:00\nBaboons – 05:04\n1:02:03 - Steve Jobs\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "1. 00:00 Intro\n2. 05:04 Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "1) 00:00 - Intro\n2) 05:04 - Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "9. 00:10:00 Intro\n10. 01:05:04 Baboons\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::minutes(10),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(1)
+                        + chrono::Duration::minutes(5)
+                        + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+    ];
+
+    for test in tests {
+        let result = chapters::from_description(test.description);
+
+        assert_eq!(result, test.expected);
+    }
+}
+
+#[test]
+fn test_from_description_with_options() {
+    struct Test {
+        description: &'static str,
+        options: chapters::DescriptionOptions,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            description: "00:00 Intro\n\n05:04 Baboons\n",
+            options: chapters::DescriptionOptions {
+                require_contiguous: true,
+                ..Default::default()
+            },
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::seconds(0),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            description: "00:00 Intro\n\n05:04 Baboons\n",
+            options: chapters::DescriptionOptions::default(),
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "00:00 Intro\n05:04.5 Baboons\n",
+            options: chapters::DescriptionOptions {
+                allow_fractional_seconds: false,
+                ..Default::default()
+            },
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("5 Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "00:00 Intro\n05:75 Baboons\n",
+            options: chapters::DescriptionOptions::default(),
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::seconds(0),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            description: "00:00 Intro\n05:75 Baboons\n",
+            options: chapters::DescriptionOptions {
+                allow_out_of_range: true,
+                ..Default::default()
+            },
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(6) + chrono::Duration::seconds(15),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "01:00:00 Intro\n01:75:90 Baboons\n",
+            options: chapters::DescriptionOptions {
+                allow_out_of_range: true,
+                ..Default::default()
+            },
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::hours(1),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(2)
+                        + chrono::Duration::minutes(16)
+                        + chrono::Duration::seconds(30),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            description: "We talk about what happened at 10:30 yesterday.\n10:30 was a big day.\n",
+            options: chapters::DescriptionOptions {
+                min_chapters: 2,
+                ..Default::default()
+            },
+            expected: Ok(vec![]),
+        },
+    ];
+
+    for test in tests {
+        let result = chapters::from_description_with_options(test.description, &test.options);
+
+        assert_eq!(result, test.expected);
+    }
+}
+
+#[test]
+fn test_from_description_html() {
+    struct Test {
+        html: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            html: "<ul><li>00:00 &ndash; Intro</li><li>05:04 &ndash; Q&amp;A with &quot;Steve&quot;&nbsp;</li></ul>",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Q&A with \"Steve\"")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            html: "<p>00:00 Intro<br/>05:04 <a href='https://example.com/baboons?a=1&amp;b=2'>Baboons</a><br/>09:58 <a href=\"/relative\">Steve Jobs</a></p>",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::seconds(0),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    link: Some(Link {
+                        url: url::Url::parse("https://example.com/baboons?a=1&b=2").unwrap(),
+                        title: None,
+                    }),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(9) + chrono::Duration::seconds(58),
+                    title: Some(String::from("Steve Jobs")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            html: "<p>No chapters, just 1 &lt; 2.</p>",
+            expected: Ok(vec![]),
+        },
+    ];
+
+    for test in tests {
+        let result = chapters::from_description_html(test.html);
+
+        assert_eq!(result, test.expected);
+    }
+}
+
+#[test]
+fn test_to_description_with_options() {
+    struct Test {
+        chapters: Vec<Chapter>,
+        options: chapters::ToDescriptionOptions,
+        expected: &'static str,
+    }
+
+    let tests = vec![
+        Test {
+            chapters: vec![
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(9) + chrono::Duration::seconds(59),
+                    title: Some(String::from("Steve Jobs")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(10),
+                    title: Some(String::from("The Movement")),
+                    ..Default::default()
+                },
+            ],
+            options: chapters::ToDescriptionOptions::default(),
+            expected: "05:04 Baboons\n09:59 Steve Jobs\n10:00 The Movement\n",
+        },
+        Test {
+            chapters: vec![
+                Chapter {
+                    start: chrono::Duration::minutes(5) + chrono::Duration::seconds(4),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(9) + chrono::Duration::seconds(59),
+                    title: Some(String::from("Steve Jobs")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(10),
+                    title: Some(String::from("The Movement")),
+                    ..Default::default()
+                },
+            ],
+            options: chapters::ToDescriptionOptions {
+                padding: chapters::Padding::Unpadded,
+                ..Default::default()
+            },
+            expected: "5:04 Baboons\n9:59 Steve Jobs\n10:00 The Movement\n",
+        },
+        Test {
+            chapters: vec![
+                Chapter {
+                    start: chrono::Duration::minutes(59) + chrono::Duration::seconds(59),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(1),
+                    title: Some(String::from("Steve Jobs")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(1)
+                        + chrono::Duration::minutes(5)
+                        + chrono::Duration::seconds(4),
+                    title: Some(String::from("The Movement")),
+                    ..Default::default()
+                },
+            ],
+            options: chapters::ToDescriptionOptions {
+                padding: chapters::Padding::Unpadded,
+                ..Default::default()
+            },
+            expected: "0:59:59 Baboons\n1:00:00 Steve Jobs\n1:05:04 The Movement\n",
+        },
+        Test {
+            chapters: vec![
+                Chapter {
+                    start: chrono::Duration::minutes(59) + chrono::Duration::seconds(59),
+                    title: Some(String::from("Baboons")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(1),
+                    title: Some(String::from("Steve Jobs")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::hours(1)
+                        + chrono::Duration::minutes(5)
+                        + chrono::Duration::seconds(4),
+                    title: Some(String::from("The Movement")),
+                    ..Default::default()
+                },
+            ],
+            options: chapters::ToDescriptionOptions {
+                hour_formatting: chapters::HourFormatting::PerChapter,
+                padding: chapters::Padding::Unpadded,
+                ..Default::default()
+            },
+            expected: "59:59 Baboons\n1:00:00 Steve Jobs\n1:05:04 The Movement\n",
+        },
+        Test {
+            chapters: vec![
+                Chapter::at_seconds(0.0, "Intro"),
+                Chapter::marker(chrono::Duration::hours(1), None),
+            ],
+            options: chapters::ToDescriptionOptions {
+                missing_title: chapters::MissingTitlePolicy::Skip,
+                ..Default::default()
+            },
+            expected: "00:00 Intro\n",
+        },
+        Test {
+            chapters: vec![
+                Chapter::at_seconds(0.0, "Intro"),
+                Chapter::marker(chrono::Duration::hours(1), None),
+            ],
+            options: chapters::ToDescriptionOptions {
+                missing_title: chapters::MissingTitlePolicy::Placeholder(String::from("Untitled")),
+                ..Default::default()
+            },
+            expected: "00:00:00 Intro\n01:00:00 Untitled\n",
+        },
+    ];
+
+    for test in tests {
+        let result = chapters::to_description_with_options(&test.chapters, &test.options);
+
+        assert_eq!(result, Ok(String::from(test.expected)));
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_json() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            end: Some(chrono::Duration::seconds(10) + chrono::Duration::milliseconds(400)),
+            title: Some(String::from("Start")),
+            language: None,
+            images: Vec::new(),
+            link: Some(Link {
+                url: url::Url::parse("https://example.com").unwrap(),
+                title: Some(String::from("Example")),
+            }),
+            image: Some(Image::from(
+                url::Url::parse("https://example.com/image.png").unwrap(),
+            )),
+            hidden: false,
+            location: None,
+            element_id: None,
+            start_byte: None,
+            end_byte: None,
+            #[cfg(feature = "rssblue")]
+            remote_entity: Some(RemoteEntity::Item {
+                feed_guid: uuid::Uuid::parse_str("917393e3-1b1e-5cef-ace4-edaa54e1f810").unwrap(),
+                guid: String::from("44a78abc-dffe-4de2-9230-6d6e723360a5"),
+            }),
+        },
+        Chapter {
+            start: chrono::Duration::seconds(10) + chrono::Duration::milliseconds(400),
+            end: None,
+            title: None,
+            language: None,
+            link: None,
+            image: None,
+            images: Vec::new(),
+            hidden: false,
+            location: None,
+            element_id: None,
+            start_byte: None,
+            end_byte: None,
+            #[cfg(feature = "rssblue")]
+            remote_entity: None,
+        },
+    ];
+
+    // ensure indentation
+    let result = serde_json::to_string_pretty(&chapters).unwrap();
+
+    cfg_if! { if #[cfg( feature = "rssblue" )]{
+    let expected = r#"[
+  {
+    "start": 0,
+    "end": 10.4,
+    "title": "Start",
+    "image": {
+      "Url": "https://example.com/image.png"
+    },
+    "link": {
+      "url": "https://example.com/",
+      "title": "Example"
+    },
+    "hidden": false,
+    "remote_entity": {
+      "item": {
+        "feed_guid": "917393e3-1b1e-5cef-ace4-edaa54e1f810",
+        "guid": "44a78abc-dffe-4de2-9230-6d6e723360a5"
+      }
+    }
+  },
+  {
+    "start": 10.4,
+    "hidden": false
+  }
+]"#;
+    } else {
+    let expected = r#"[
+  {
+    "start": 0,
+    "end": 10.4,
+    "title": "Start",
+    "image": {
+      "Url": "https://example.com/image.png"
+    },
+    "link": {
+      "url": "https://example.com/",
+      "title": "Example"
+    },
+    "hidden": false
+  },
+  {
+    "start": 10.4,
+    "hidden": false
+  }
+]"#;
+    }}
+
+    assert_eq!(result, expected);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_from_json_strict() {
+    let json = r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0,
+      "title": "Intro",
+      "img": "",
+      "url": "https://example.com/"
+    },
+    {
+      "startTime": 30,
+      "title": "Baboons",
+      "url": "not a url"
+    }
+  ]
+}"#;
+
+    assert_eq!(
+        chapters::from_json_strict(json.as_bytes()),
+        Err(String::from(
+            "Invalid URL `not a url` in `url` of chapter 1: relative URL without a base"
+        ))
+    );
+
+    let valid = json.replace("not a url", "https://example.com/baboons");
+    assert_eq!(
+        chapters::from_json_strict(valid.as_bytes()),
+        from_json(valid.as_bytes())
+    );
+    assert_eq!(
+        chapters::from_json_strict(valid.as_bytes()).unwrap()[0].image,
+        None
+    );
+
+    let quoted = valid.replace("\"startTime\": 30", "\"startTime\": \"30\"");
+    assert_eq!(
+        chapters::from_json_strict(quoted.as_bytes()),
+        Err(String::from(
+            "Time `30` in `startTime` of chapter 1 is a string, not a number"
+        ))
+    );
+    assert_eq!(from_json(quoted.as_bytes()), from_json(valid.as_bytes()));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_image_metadata() {
+    let chapters = vec![Chapter {
+        start: chrono::Duration::zero(),
+        title: Some(String::from("Intro")),
+        image: Some(Image::Url {
+            url: url::Url::parse("https://example.com/intro.jpg").unwrap(),
+            mime_type: Some(String::from("image/jpeg")),
+            width: Some(1400),
+            height: Some(1400),
+        }),
+        ..Default::default()
+    }];
+
+    let json = chapters::to_json(&chapters).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0,
+      "title": "Intro",
+      "img": "https://example.com/intro.jpg",
+      "imgType": "image/jpeg",
+      "imgWidth": 1400,
+      "imgHeight": 1400
+    }
+  ]
+}"#
+    );
+    assert_eq!(from_json(json.as_bytes()), Ok(chapters.clone()));
+
+    assert_eq!(
+        serde_json::to_string(&chapters[0].image).unwrap(),
+        r#"{"Url":{"url":"https://example.com/intro.jpg","mime_type":"image/jpeg","width":1400,"height":1400}}"#
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_untitled_marker() {
+    let chapters = vec![
+        Chapter::at_seconds(0.0, "Intro"),
+        Chapter::marker(chrono::Duration::seconds(90), None),
+    ];
+
+    let json: serde_json::Value =
+        serde_json::from_str(&chapters::to_json(&chapters).unwrap()).unwrap();
+    assert_eq!(json["chapters"][1], serde_json::json!({ "startTime": 90 }));
+    assert_eq!(from_json(json.to_string().as_bytes()), Ok(chapters.clone()));
+
+    assert_eq!(
+        chapters::to_description(&chapters),
+        Err(String::from("Chapter 1 has no title"))
+    );
+    assert_eq!(
+        chapters::to_markdown(&chapters),
+        Err(String::from("Chapter 1 has no title"))
+    );
+
+    // Formats where the title is optional write the marker without one.
+    for format in [
+        chapters::ChapterFormat::Psc,
+        chapters::ChapterFormat::FfMetadata,
+    ] {
+        let written = chapters::write(&chapters, format).unwrap();
+        let read_back = chapters::read(written.as_bytes(), format).unwrap();
+        assert_eq!(read_back[1].title, None);
+    }
+}
+
+#[test]
+fn test_chapter_hash() {
+    let image = Image::Data {
+        mime_type: String::from("image/png"),
+        data: vec![1, 2, 3],
+    };
+    let chapter = Chapter {
+        start: chrono::Duration::seconds(1),
+        title: Some(String::from("Intro")),
+        image: Some(image.clone()),
+        link: Some(Link {
+            url: url::Url::parse("https://example.com").unwrap(),
+            title: None,
+        }),
+        ..Default::default()
+    };
+    let other_image = Chapter {
+        image: Some(Image::Data {
+            mime_type: String::from("image/png"),
+            data: vec![1, 2, 4],
+        }),
+        ..chapter.clone()
+    };
+    let other_title = Chapter {
+        title: Some(String::from("Outro")),
+        ..chapter.clone()
+    };
+
+    let chapters: std::collections::HashSet<Chapter> = vec![
+        chapter.clone(),
+        other_image.clone(),
+        chapter.clone(),
+        other_title.clone(),
+        other_image.clone(),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(chapters.len(), 3);
+    assert!(chapters.contains(&chapter));
+    assert!(chapters.contains(&other_image));
+    assert!(chapters.contains(&other_title));
+}
+
+#[cfg(all(feature = "json", feature = "mp3"))]
+#[test]
+fn test_chapter_language() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::zero(),
+            title: Some(String::from("Introducción")),
+            language: Some(String::from("es")),
+            element_id: Some(String::from("chp1")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(42),
+            title: Some(String::from("Status quo")),
+            element_id: Some(String::from("chp2")),
+            ..Default::default()
+        },
+    ];
+
+    let json = chapters::to_json(&chapters).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0,
+      "title": "Introducción",
+      "language": "es"
+    },
+    {
+      "startTime": 42,
+      "title": "Status quo"
+    }
+  ]
+}"#
+    );
+    let chapters_without_ids: Vec<Chapter> = chapters
+        .iter()
+        .cloned()
+        .map(|chapter| Chapter {
+            element_id: None,
+            ..chapter
+        })
+        .collect();
+    assert_eq!(from_json(json.as_bytes()), Ok(chapters_without_ids));
+
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-language.mp3");
+    chapters::to_mp3_file(src_path, dst_path.as_path(), &chapters).unwrap();
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(chapters_read, chapters);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_json_with_decimals() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::zero(),
+            end: Some(chrono::Duration::seconds(30) + chrono::Duration::milliseconds(500)),
+            title: Some(String::from("Intro")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(30) + chrono::Duration::milliseconds(500),
+            title: Some(String::from("Baboons")),
+            image: Some(Image::Url {
+                url: url::Url::parse("https://example.com/baboons.jpg").unwrap(),
+                mime_type: None,
+                width: Some(640),
+                height: Some(480),
+            }),
+            ..Default::default()
+        },
+    ];
+
+    let options = chapters::JsonOptions {
+        decimals: Some(3),
+        ..Default::default()
+    };
+    let json = chapters::to_json_with_options(&chapters, &options).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0.000,
+      "endTime": 30.500,
+      "title": "Intro"
+    },
+    {
+      "startTime": 30.500,
+      "title": "Baboons",
+      "img": "https://example.com/baboons.jpg",
+      "imgWidth": 640,
+      "imgHeight": 480
+    }
+  ]
+}"#
+    );
+    assert_eq!(chapters::from_json(json.as_bytes()), Ok(chapters));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_json_with_explicit_toc() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::zero(),
+            title: Some(String::from("Intro")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(30),
+            title: Some(String::from("Ad")),
+            hidden: true,
+            ..Default::default()
+        },
+    ];
+
+    let options = chapters::JsonOptions {
+        explicit_toc: true,
+        ..Default::default()
+    };
+    let json = chapters::to_json_with_options(&chapters, &options).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0,
+      "title": "Intro",
+      "toc": true
+    },
+    {
+      "startTime": 30,
+      "title": "Ad",
+      "toc": false
+    }
+  ]
+}"#
+    );
+    assert_eq!(chapters::from_json(json.as_bytes()), Ok(chapters));
+}
+
+#[cfg(feature = "rssblue")]
+#[test]
+fn test_json_multiple_images() {
+    let chapters = vec![Chapter {
+        start: chrono::Duration::zero(),
+        title: Some(String::from("Intro")),
+        image: Some(Image::from(
+            url::Url::parse("https://example.com/intro-thumbnail.jpg").unwrap(),
+        )),
+        images: vec![Image::from(
+            url::Url::parse("https://example.com/intro.jpg").unwrap(),
+        )],
+        ..Default::default()
+    }];
+
+    let json = chapters::to_json(&chapters).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0,
+      "title": "Intro",
+      "img": "https://example.com/intro-thumbnail.jpg",
+      "rssblue:images": [
+        "https://example.com/intro.jpg"
+      ]
+    }
+  ]
+}"#
+    );
+    assert_eq!(from_json(json.as_bytes()), Ok(chapters));
+}
+
+#[cfg(feature = "rssblue")]
+#[test]
+fn test_json_remote_entity_round_trip() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::zero(),
+            title: Some(String::from("Feed")),
+            remote_entity: Some(RemoteEntity::Feed {
+                guid: uuid::Uuid::parse_str("917393e3-1b1e-5cef-ace4-edaa54e1f810").unwrap(),
+            }),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(30),
+            title: Some(String::from("Item")),
+            remote_entity: Some(RemoteEntity::Item {
+                feed_guid: uuid::Uuid::parse_str("917393e3-1b1e-5cef-ace4-edaa54e1f810").unwrap(),
+                guid: String::from("44a78abc-dffe-4de2-9230-6d6e723360a5"),
+            }),
+            ..Default::default()
+        },
+    ];
+
+    let json = chapters::to_json(&chapters).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{
+  "version": "1.2.0",
+  "chapters": [
+    {
+      "startTime": 0,
+      "title": "Feed",
+      "rssblue:remoteEntity": {
+        "feed": {
+          "guid": "917393e3-1b1e-5cef-ace4-edaa54e1f810"
+        }
+      }
+    },
+    {
+      "startTime": 30,
+      "title": "Item",
+      "rssblue:remoteEntity": {
+        "item": {
+          "feed_guid": "917393e3-1b1e-5cef-ace4-edaa54e1f810",
+          "guid": "44a78abc-dffe-4de2-9230-6d6e723360a5"
+        }
+      }
+    }
+  ]
+}"#
+    );
+    assert_eq!(from_json(json.as_bytes()), Ok(chapters));
+}
+
+#[test]
+fn test_psc() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            title: Some(String::from("Intro")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::hours(1)
+                + chrono::Duration::minutes(5)
+                + chrono::Duration::milliseconds(250),
+            title: Some(String::from("<Baboons> & 'apes'")),
+            link: Some(Link {
+                url: url::Url::parse("https://example.com/?a=1&b=2").unwrap(),
+                title: None,
+            }),
+            image: Some(Image::from(
+                url::Url::parse("https://example.com/image.png").unwrap(),
+            )),
+            ..Default::default()
+        },
+    ];
+
+    let psc = chapters::to_psc(&chapters).unwrap();
+    let result = chapters::from_psc(psc.as_bytes()).unwrap();
+
+    assert_eq!(result, chapters);
+}
+
+#[test]
+fn test_psc_invalid_timestamps() {
+    for start in [
+        "99999999999999999:00:00",
+        "9223372036854775807",
+        "00:aa:00",
+        "1:2:3:4",
+        "00:00:00.1234",
+        "-5",
+        "+5",
+        "00:-1:00",
+        "1:-5",
+        "0.-5",
+        "00:75:00",
+        "00:00:60",
+    ] {
+        let psc = format!(
+            r#"<psc:chapters version="1.2" xmlns:psc="http://podlove.org/simple-chapters"><psc:chapter start="{}" /></psc:chapters>"#,
+            start
+        );
+
+        assert_eq!(
+            chapters::read(psc.as_bytes(), chapters::ChapterFormat::Psc),
+            Err(format!("Invalid PSC timestamp `{}`", start))
+        );
+    }
+}
+
+#[test]
+fn test_cue_invalid_timestamps() {
+    for index in [
+        "999999999999999:00:00",
+        "-1:00:00",
+        "00:-1:00",
+        "00:00:75",
+        "00:00",
+    ] {
+        let cue = format!(
+            "FILE \"a.mp3\" MP3\n  TRACK 01 AUDIO\n    INDEX 01 {}\n",
+            index
+        );
+
+        assert_eq!(
+            chapters::from_cue(cue.as_bytes()),
+            Err(format!("Invalid CUE timestamp `{}`", index))
+        );
+    }
+}
+
+#[test]
+fn test_cue_large_start() {
+    let chapters = vec![Chapter {
+        start: chrono::Duration::milliseconds(i64::MAX / 10),
+        ..Default::default()
+    }];
+
+    assert_eq!(
+        chapters::to_cue(&chapters, "a.mp3"),
+        Err(format!(
+            "Chapter start time ({} ms) is too large to be written to a CUE sheet",
+            i64::MAX / 10
+        ))
+    );
+}
+
+#[test]
+fn test_marker_tsv_invalid_timecodes() {
+    struct Test {
+        tsv: &'static str,
+        fps: f64,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            tsv: "Intro\t00:00:01:00\t00:00:02:00\n",
+            fps: 25.0,
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::seconds(1),
+                end: Some(chrono::Duration::seconds(3)),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            tsv: "Intro\t999999999999999999:00:00:00\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from(
+                "Invalid timecode `999999999999999999:00:00:00`",
+            )),
+        },
+        Test {
+            tsv: "Intro\t99999999999999:00:00;00\t00:00:00;00\n",
+            fps: 29.97,
+            expected: Err(String::from("Invalid timecode `99999999999999:00:00;00`")),
+        },
+        Test {
+            tsv: "Intro\t2562047788015:00:00:00\t2562047788015:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from(
+                "Marker line `Intro\t2562047788015:00:00:00\t2562047788015:00:00:00` ends out of range",
+            )),
+        },
+        Test {
+            tsv: "Intro\t00:-1:00:00\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `00:-1:00:00`")),
+        },
+        Test {
+            tsv: "Intro\t-1:00:00:00\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `-1:00:00:00`")),
+        },
+        Test {
+            tsv: "Intro\t00:00:00:+1\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `00:00:00:+1`")),
+        },
+        Test {
+            tsv: "Intro\t00:60:00:00\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `00:60:00:00`")),
+        },
+        Test {
+            tsv: "Intro\t00:00:60:00\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `00:00:60:00`")),
+        },
+        Test {
+            tsv: "Intro\t00:00:00:25\t00:00:00:00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `00:00:00:25`")),
+        },
+        Test {
+            tsv: "Intro\t00:01:00;00\t00:00:00;00\n",
+            fps: 25.0,
+            expected: Err(String::from("Invalid timecode `00:01:00;00`")),
+        },
+        Test {
+            tsv: "Intro\t00:01:00;02\t00:00:00;00\n",
+            fps: 29.97,
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::milliseconds(60_060),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+    ];
+
+    for test in tests {
+        assert_eq!(
+            chapters::from_marker_tsv(test.tsv.as_bytes(), test.fps),
+            test.expected
+        );
+    }
+}
+
+#[cfg(feature = "hls")]
+#[test]
+fn test_hls_invalid_durations() {
+    struct Test {
+        date_range: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            date_range: r#"START-DATE="2023-10-01T12:00:00.000Z",DURATION=1.5"#,
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::zero(),
+                end: Some(chrono::Duration::milliseconds(1500)),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            date_range: r#"START-DATE="2023-10-01T12:00:00.000Z",DURATION=1e300"#,
+            expected: Err(String::from(
+                "Invalid duration `1e300`, expected a non-negative number of seconds",
+            )),
+        },
+        Test {
+            date_range: r#"START-DATE="2023-10-01T12:00:00.000Z",DURATION=NaN"#,
+            expected: Err(String::from(
+                "Invalid duration `NaN`, expected a non-negative number of seconds",
+            )),
+        },
+        Test {
+            date_range: r#"START-DATE="2023-10-01T12:00:00.000Z",DURATION=-5"#,
+            expected: Err(String::from(
+                "Invalid duration `-5`, expected a non-negative number of seconds",
+            )),
+        },
+        Test {
+            date_range: r#"START-DATE="2023-10-01T12:00:00.000Z",DURATION=9000000000000"#,
+            expected: Err(String::from(
+                "Date range with duration `9000000000000` is out of range",
+            )),
+        },
+    ];
+
+    for test in tests {
+        let playlist = format!("#EXTM3U\n#EXT-X-DATERANGE:{}\n", test.date_range);
+        assert_eq!(chapters::from_hls_playlist(&playlist), test.expected);
+    }
+}
+
+#[cfg(feature = "hls")]
+#[test]
+fn test_hls_dateranges_out_of_range() {
+    let start_date = chrono::DateTime::parse_from_rfc3339("2023-10-01T12:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let chapters = vec![
+        Chapter::at_seconds(0.0, "Intro"),
+        Chapter {
+            start: chrono::Duration::days(100_000_000),
+            ..Default::default()
+        },
+    ];
+
+    assert_eq!(
+        chapters::to_hls_dateranges(&chapters, start_date),
+        Err(String::from("Chapter 1 starts at an out-of-range date"))
+    );
+}
+
+/// Builds a minimal Ogg Opus file whose comment header holds the given comments.
+fn opus_file_with_comments(comments: &[&str]) -> Vec<u8> {
+    let vendor = "chapters";
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend((vendor.len() as u32).to_le_bytes());
+    tags.extend(vendor.as_bytes());
+    tags.extend((comments.len() as u32).to_le_bytes());
+    for comment in comments {
+        tags.extend((comment.len() as u32).to_le_bytes());
+        tags.extend(comment.as_bytes());
+    }
+
+    let mut file = Vec::new();
+    for (sequence, packet) in [b"OpusHead".to_vec(), tags].into_iter().enumerate() {
+        // A packet is split into 255-byte segments followed by a shorter one.
+        let mut segments = vec![255; packet.len() / 255];
+        segments.push((packet.len() % 255) as u8);
+
+        file.extend(b"OggS\0");
+        file.push(if sequence == 0 { 2 } else { 0 });
+        file.extend(0u64.to_le_bytes());
+        file.extend(1u32.to_le_bytes());
+        file.extend((sequence as u32).to_le_bytes());
+        file.extend(0u32.to_le_bytes());
+        file.push(segments.len() as u8);
+        file.extend(segments);
+        file.extend(packet);
+    }
+    file
+}
+
+#[test]
+fn test_opus_invalid_timestamps() {
+    let dst_path = std::env::temp_dir().join("chapters-test-opus-invalid-timestamps.opus");
+
+    struct Test {
+        comments: Vec<&'static str>,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            comments: vec!["CHAPTER001=01:02:03.5", "CHAPTER001NAME=Intro"],
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::milliseconds(3_723_500),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            comments: vec!["CHAPTER001=99999999999999:00:00.000"],
+            expected: Err(String::from(
+                "Invalid chapter timestamp `99999999999999:00:00.000`",
+            )),
+        },
+        Test {
+            comments: vec!["CHAPTER001=-1:00:00.000"],
+            expected: Err(String::from("Invalid chapter timestamp `-1:00:00.000`")),
+        },
+        Test {
+            comments: vec!["CHAPTER001=00:-1:00.000"],
+            expected: Err(String::from("Invalid chapter timestamp `00:-1:00.000`")),
+        },
+    ];
+
+    for test in tests {
+        std::fs::write(&dst_path, opus_file_with_comments(&test.comments)).unwrap();
+        let chapters = chapters::from_opus_file(&dst_path);
+        std::fs::remove_file(&dst_path).unwrap();
+
+        assert_eq!(chapters, test.expected);
+    }
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_top_level_chapter_images() {
+    use id3::TagLike;
+
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-top-level-chapter-images.mp3");
+    std::fs::copy(src_path, &dst_path).unwrap();
+
+    let mut tag = id3::Tag::read_from_path(&dst_path).unwrap();
+    let element_id = tag.chapters().next().unwrap().element_id.clone();
+    tag.add_frame(id3::frame::Picture {
+        mime_type: String::from("image/png"),
+        picture_type: id3::frame::PictureType::Other,
+        description: element_id,
+        data: vec![0x89, 0x50, 0x4e, 0x47],
+    });
+    tag.write_to_path(&dst_path, id3::Version::Id3v24).unwrap();
+
+    let chapters = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(
+        chapters
+            .iter()
+            .filter(|chapter| chapter.image.is_some())
+            .count(),
+        // One of the chapters already has an embedded image.
+        2
+    );
+    assert!(chapters.iter().any(|chapter| chapter.image
+        == Some(Image::Data {
+            mime_type: String::from("image/png"),
+            data: vec![0x89, 0x50, 0x4e, 0x47],
+        })));
+}
+
+#[test]
+fn test_mp4_chunk_offsets_after_writing() {
+    let src_path = std::path::Path::new("tests/data/mp4-chapters.quicktime.m4a");
+    let dst_path = std::env::temp_dir().join("chapters-test-chunk-offsets.m4a");
+
+    let chapters = vec![Chapter {
+        start: chrono::Duration::zero(),
+        title: Some(String::from("A title long enough to move the media data")),
+        ..Default::default()
+    }];
+    chapters::to_mp4_file(src_path, dst_path.as_path(), &chapters).unwrap();
+
+    let src = std::fs::read(src_path).unwrap();
+    let dst = std::fs::read(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+    assert!(dst.len() > src.len());
+
+    // The last `stco` box belongs to the chapter track, whose first sample is "Intro".
+    let stco = dst
+        .windows(4)
+        .rposition(|window| window == b"stco")
+        .unwrap();
+    let chunk_offset = u32::from_be_bytes(dst[stco + 12..stco + 16].try_into().unwrap()) as usize;
+    assert_eq!(&dst[chunk_offset..chunk_offset + 7], b"\x00\x05Intro");
+}
+
+#[test]
+fn test_mp4_huge_stts_sample_count() {
+    let src_path = std::path::Path::new("tests/data/mp4-chapters.quicktime.m4a");
+    let dst_path = std::env::temp_dir().join("chapters-test-huge-stts-sample-count.m4a");
+
+    // The last `stts` box belongs to the chapter track; claim that its first entry covers 2^32 - 1
+    // samples.
+    let mut bytes = std::fs::read(src_path).unwrap();
+    let stts = bytes
+        .windows(4)
+        .rposition(|window| window == b"stts")
+        .unwrap();
+    bytes[stts + 12..stts + 16].copy_from_slice(&u32::MAX.to_be_bytes());
+    std::fs::write(&dst_path, &bytes).unwrap();
+
+    let chapters = chapters::from_mp4_file(&dst_path);
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(
+        chapters.map(|chapters| chapters.len()),
+        chapters::from_mp4_file(src_path).map(|chapters| chapters.len())
+    );
+}
+
+#[test]
+fn test_mp4_huge_box_size() {
+    let path = std::env::temp_dir().join("chapters-test-huge-box-size.m4a");
+
+    // An empty `ftyp` box followed by a `free` box with a 64-bit size of 2^64 - 1.
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"\0\0\0\x08ftyp\0\0\0\x01free");
+    bytes.extend_from_slice(&u64::MAX.to_be_bytes());
+    std::fs::write(&path, &bytes).unwrap();
+
+    let chapters = chapters::from_mp4_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        chapters,
+        Err(format!(
+            "Error reading MP4 file `{}`: box `free` at offset 8 has an invalid size of {} bytes",
+            path.display(),
+            u64::MAX
+        ))
+    );
+}
+
+#[test]
+fn test_mp4_huge_stsz_sample_size() {
+    let src_path = std::path::Path::new("tests/data/mp4-chapters.quicktime.m4a");
+    let dst_path = std::env::temp_dir().join("chapters-test-huge-stsz-sample-size.m4a");
+
+    // The last `stsz` box belongs to the chapter track; claim that its first sample is 4 GiB.
+    let mut bytes = std::fs::read(src_path).unwrap();
+    let stsz = bytes
+        .windows(4)
+        .rposition(|window| window == b"stsz")
+        .unwrap();
+    bytes[stsz + 16..stsz + 20].copy_from_slice(&u32::MAX.to_be_bytes());
+    std::fs::write(&dst_path, &bytes).unwrap();
+
+    let chapters = chapters::from_mp4_file(&dst_path);
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(
+        chapters,
+        Err(format!(
+            "Error reading MP4 file `{}`: chapter sample at offset 848 with a size of {} bytes \
+             doesn't fit in the file",
+            dst_path.display(),
+            u32::MAX
+        ))
+    );
+}
+
+#[test]
+fn test_mp4_huge_start() {
+    let src_path = std::path::Path::new("tests/data/mp4-chapters.nero.m4a");
+    let dst_path = std::env::temp_dir().join("chapters-test-huge-start.m4a");
+
+    let start = chrono::Duration::microseconds((u64::MAX / 10 + 1) as i64);
+    let chapters = vec![Chapter {
+        start,
+        ..Default::default()
+    }];
+
+    assert_eq!(
+        chapters::to_mp4_file(src_path, dst_path.as_path(), &chapters),
+        Err(format!("Chapter start `{}` cannot be stored", start))
+    );
+    let _ = std::fs::remove_file(&dst_path);
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_hidden_chapters() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-hidden-chapters.mp3");
+
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            end: Some(chrono::Duration::seconds(42)),
+            title: Some(String::from("Introduction")),
+            element_id: Some(String::from("chp1")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(42),
+            end: Some(chrono::Duration::seconds(50)),
+            title: Some(String::from("Applause")),
+            element_id: Some(String::from("chp2")),
+            hidden: true,
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(50),
+            title: Some(String::from("Status quo")),
+            element_id: Some(String::from("chp3")),
+            ..Default::default()
+        },
+    ];
+
+    chapters::to_mp3_file(src_path, dst_path.as_path(), &chapters).unwrap();
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(chapters_read, chapters);
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_embedded_chapter_images() {
+    use id3::TagLike;
+
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-embedded-chapter-images.mp3");
+    std::fs::copy(src_path, &dst_path).unwrap();
+
+    let mut tag = id3::Tag::read_from_path(&dst_path).unwrap();
+    let mut id3_chapters: Vec<id3::frame::Chapter> = tag.chapters().cloned().collect();
+    id3_chapters.sort_by_key(|chapter| chapter.start_time);
+    id3_chapters[1].frames.push(id3::frame::Frame::with_content(
+        "APIC",
+        id3::Content::Picture(id3::frame::Picture {
+            mime_type: String::from("image/jpeg"),
+            picture_type: id3::frame::PictureType::Other,
+            description: String::new(),
+            data: vec![0xff, 0xd8, 0xff, 0xe0],
+        }),
+    ));
+    tag.remove_all_chapters();
+    for id3_chapter in id3_chapters {
+        tag.add_frame(id3_chapter);
+    }
+    tag.write_to_path(&dst_path, id3::Version::Id3v24).unwrap();
+
+    let chapters = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(chapters[0].image, None);
+    assert_eq!(
+        chapters[1].image,
+        Some(Image::Data {
+            mime_type: String::from("image/jpeg"),
+            data: vec![0xff, 0xd8, 0xff, 0xe0],
+        })
+    );
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_chapter_images_round_trip() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-chapter-images-round-trip.mp3");
+
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            title: Some(String::from("Introduction")),
+            element_id: Some(String::from("chp1")),
+            image: Some(Image::Data {
+                mime_type: String::from("image/jpeg"),
+                data: include_bytes!(
+                    "data/id3-chapters.jfk-rice-university-speech.on-being-first.jpg"
+                )
+                .to_vec(),
+            }),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(42),
+            title: Some(String::from("Status quo")),
+            element_id: Some(String::from("chp2")),
+            image: Some(Image::from(
+                url::Url::parse("https://example.com/status-quo.png").unwrap(),
+            )),
+            images: vec![
+                Image::from(url::Url::parse("https://example.com/status-quo-full.png").unwrap()),
+                Image::Data {
+                    mime_type: String::from("image/jpeg"),
+                    data: include_bytes!(
+                        "data/id3-chapters.jfk-rice-university-speech.on-being-first.jpg"
+                    )
+                    .to_vec(),
+                },
+            ],
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(308),
+            title: Some(String::from("On being first")),
+            element_id: Some(String::from("chp3")),
+            ..Default::default()
+        },
+    ];
+
+    chapters::to_mp3_file(src_path, dst_path.as_path(), &chapters).unwrap();
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(chapters_read, chapters);
+}
+
+#[cfg(feature = "fetch")]
+#[test]
+fn test_from_chapters_url() {
+    use std::io::{BufRead, BufReader, Write};
+
+    struct Test {
+        status_line: &'static str,
+        content_type: &'static str,
+        body: &'static str,
+        expected: Result<Vec<Chapter>, &'static str>,
+    }
+
+    let tests = vec![
+        Test {
+            status_line: "HTTP/1.1 200 OK",
+            content_type: "application/json+chapters",
+            body: r#"{"version": "1.2.0", "chapters": [{"startTime": 0, "title": "Intro"}]}"#,
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::zero(),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            status_line: "HTTP/1.1 404 Not Found",
+            content_type: "text/plain",
+            body: "Not found",
+            expected: Err("Unexpected HTTP status 404"),
+        },
+        Test {
+            status_line: "HTTP/1.1 200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: "<html></html>",
+            expected: Err("Unexpected content type `text/html`"),
+        },
+    ];
+
+    for test in tests {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = url::Url::parse(&format!(
+            "http://{}/chapters.json",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read the request headers before responding.
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "{}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                test.status_line,
+                test.content_type,
+                test.body.len(),
+                test.body
+            )
+            .unwrap();
+        });
+
+        let result = chapters::from_chapters_url(&url);
+        server.join().unwrap();
+
+        match test.expected {
+            Ok(expected) => assert_eq!(result, Ok(expected)),
+            Err(expected) => assert!(
+                result.as_ref().is_err_and(|e| e.starts_with(expected)),
+                "{:?} does not start with {:?}",
+                result,
+                expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_sentinel_end_time() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-sentinel-end-time.mp3");
+    std::fs::copy(src_path, &dst_path).unwrap();
+
+    use id3::TagLike;
+
+    let mut tag = id3::Tag::new();
+    tag.add_frame(id3::frame::Chapter {
+        element_id: String::from("chp1"),
+        start_time: 9_000,
+        end_time: u32::MAX,
+        start_offset: u32::MAX,
+        end_offset: u32::MAX,
+        frames: vec![id3::frame::Frame::with_content(
+            "TIT2",
+            id3::Content::Text(String::from("Thanks")),
+        )],
+    });
+    tag.write_to_path(&dst_path, id3::Version::Id3v24).unwrap();
+
+    let options = chapters::Mp3Options {
+        instants_have_no_end: false,
+    };
+    let chapters_read = chapters::from_mp3_file_with_options(&dst_path, &options).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(
+        chapters_read,
+        vec![Chapter {
+            start: chrono::Duration::seconds(9),
+            title: Some(String::from("Thanks")),
+            element_id: Some(String::from("chp1")),
+            ..Default::default()
+        }]
+    );
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_id3v23_round_trip() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-id3v23-round-trip.mp3");
+
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            end: Some(chrono::Duration::seconds(42)),
+            title: Some(String::from("Introduction")),
+            element_id: Some(String::from("chp1")),
+            link: Some(Link {
+                url: url::Url::parse("https://www.rice.edu").unwrap(),
+                title: Some(String::from("Rice University")),
+            }),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(42),
+            title: Some(String::from("Status quo")),
+            element_id: Some(String::from("chp2")),
+            ..Default::default()
+        },
+    ];
+
+    chapters::to_mp3_file_with_version(
+        src_path,
+        dst_path.as_path(),
+        &chapters,
+        id3::Version::Id3v23,
+    )
+    .unwrap();
+    let version = id3::Tag::read_from_path(&dst_path).unwrap().version();
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(version, id3::Version::Id3v23);
+    assert_eq!(chapters_read, chapters);
+}
+
+#[cfg(all(feature = "json", feature = "mp3"))]
+#[test]
+fn test_mp3_sub_second_round_trip() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-sub-second-round-trip.mp3");
+
+    let json = r#"{
+        "version": "1.2.0",
+        "chapters": [
+            {"startTime": 0.029, "title": "A"},
+            {"startTime": 0.1, "title": "B"},
+            {"startTime": 1.001, "title": "B2"},
+            {"startTime": 30.5, "endTime": 130.5, "title": "C"},
+            {"startTime": 130.5, "title": "D"}
+        ]
+    }"#;
+    let chapters = from_json(json.as_bytes()).unwrap();
+
+    // Seconds are rounded to the nearest millisecond rather than truncated, e.g., `1.001 * 1000.0`
+    // is slightly less than 1001.
+    let milliseconds = |chapters: &[Chapter]| -> Vec<(i64, Option<i64>)> {
+        chapters
+            .iter()
+            .map(|c| {
+                (
+                    c.start.num_milliseconds(),
+                    c.end.map(|end| end.num_milliseconds()),
+                )
+            })
+            .collect()
+    };
+    let expected = vec![
+        (29, None),
+        (100, None),
+        (1_001, None),
+        (30_500, Some(130_500)),
+        (130_500, None),
+    ];
+    assert_eq!(milliseconds(&chapters), expected);
+
+    chapters::to_mp3_file(src_path, dst_path.as_path(), &chapters).unwrap();
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(milliseconds(&chapters_read), expected);
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_misplaced_tag() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-misplaced-tag.mp3");
+
+    let expected = chapters::from_mp3_file(src_path).unwrap();
+    let bytes = std::fs::read(src_path).unwrap();
+    // The tag size in the header is a 28-bit "syncsafe" integer excluding the 10-byte header.
+    let tag_len = 10
+        + bytes[6..10]
+            .iter()
+            .fold(0, |size, byte| (size << 7) | *byte as usize);
+    let (tag, audio) = bytes.split_at(tag_len);
+
+    struct Test {
+        bytes: Vec<u8>,
+        expected: Vec<Chapter>,
+    }
+
+    let tests = vec![
+        // Garbage before the tag.
+        Test {
+            bytes: [&b"\0\0garbage"[..], &bytes].concat(),
+            expected: expected.clone(),
+        },
+        // Tag appended to the end.
+        Test {
+            bytes: [audio, tag].concat(),
+            expected: expected.clone(),
+        },
+        // No tag at all.
+        Test {
+            bytes: audio.to_vec(),
+            expected: vec![],
+        },
+    ];
+
+    for test in tests {
+        std::fs::write(&dst_path, &test.bytes).unwrap();
+        let chapters = chapters::from_mp3_file(&dst_path);
+        std::fs::remove_file(&dst_path).unwrap();
+
+        assert_eq!(chapters, Ok(test.expected));
+    }
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_id3v22_chapters() {
+    use id3::TagLike;
+
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-id3v22-chapters.mp3");
+    std::fs::copy(src_path, &dst_path).unwrap();
+
+    // Element ID, start and end times, start and end offsets, and a `TT2` title subframe.
+    let mut data = b"chp1\0".to_vec();
+    for field in [9_000u32, 42_000, u32::MAX, u32::MAX] {
+        data.extend(field.to_be_bytes());
+    }
+    data.extend(b"TT2\x00\x00\x07\x00Thanks\0");
+
+    let mut tag = id3::Tag::new();
+    tag.add_frame(id3::frame::Frame::with_content(
+        "CHP",
+        id3::Content::Unknown(id3::frame::Unknown {
+            data,
+            version: id3::Version::Id3v22,
+        }),
+    ));
+    tag.write_to_path(&dst_path, id3::Version::Id3v22).unwrap();
+
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    let write_result =
+        chapters::to_mp3_file_with_version(src_path, &dst_path, &[], id3::Version::Id3v22);
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(
+        chapters_read,
+        vec![Chapter {
+            start: chrono::Duration::seconds(9),
+            end: Some(chrono::Duration::seconds(42)),
+            title: Some(String::from("Thanks")),
+            element_id: Some(String::from("chp1")),
+            ..Default::default()
+        }]
+    );
+    assert_eq!(
+        write_result,
+        Err(String::from("Chapters cannot be written to ID3v2.2 tags"))
+    );
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_element_ids() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-element-ids.mp3");
+
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            title: Some(String::from("Introduction")),
+            element_id: Some(String::from("intro")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(9),
+            title: Some(String::from("Thanks")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(42),
+            title: Some(String::from("Status quo")),
+            element_id: Some(String::from("chp2")),
+            ..Default::default()
+        },
+    ];
+
+    chapters::to_mp3_file(src_path, dst_path.as_path(), &chapters).unwrap();
+    let element_ids: Vec<Option<String>> = chapters::from_mp3_file(&dst_path)
+        .unwrap()
+        .into_iter()
+        .map(|chapter| chapter.element_id)
+        .collect();
+
+    let mut duplicates = chapters.clone();
+    duplicates[1].element_id = Some(String::from("intro"));
+    let result = chapters::to_mp3_file(src_path, dst_path.as_path(), &duplicates);
+    let mut reserved = chapters.clone();
+    reserved[1].element_id = Some(String::from("toc"));
+    let reserved_result = chapters::to_mp3_file(src_path, dst_path.as_path(), &reserved);
+    let options = chapters::Mp3WriteOptions {
+        element_id_prefix: String::from("章"),
+        ..Default::default()
+    };
+    let non_latin1_result =
+        chapters::to_mp3_file_with_options(src_path, dst_path.as_path(), &chapters, &options);
+    std::fs::remove_file(&dst_path).unwrap();
+
+    // "chp2" is already taken, so the chapter without an element ID gets "chp3".
+    assert_eq!(
+        element_ids,
+        vec![
+            Some(String::from("intro")),
+            Some(String::from("chp3")),
+            Some(String::from("chp2")),
+        ]
+    );
+    assert_eq!(
+        result,
+        Err(String::from("Duplicate chapter element ID `intro`"))
+    );
+    assert_eq!(
+        reserved_result,
+        Err(String::from(
+            "Chapter element ID `toc` is reserved for the table of contents"
+        ))
+    );
+    assert_eq!(
+        non_latin1_result,
+        Err(String::from(
+            "Chapter element ID `章2` is not a non-empty ISO-8859-1 string without null characters"
+        ))
+    );
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_byte_offsets() {
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-byte-offsets.mp3");
+
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::seconds(0),
+            title: Some(String::from("Introduction")),
+            element_id: Some(String::from("chp1")),
+            start_byte: Some(0),
+            end_byte: Some(145_000),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::seconds(9),
+            title: Some(String::from("Thanks")),
+            element_id: Some(String::from("chp2")),
+            ..Default::default()
+        },
+    ];
+
+    chapters::to_mp3_file(src_path, dst_path.as_path(), &chapters).unwrap();
+    let chapters_read = chapters::from_mp3_file(&dst_path).unwrap();
+    let offsets: Vec<(u32, u32)> = id3::Tag::read_from_path(&dst_path)
+        .unwrap()
+        .chapters()
+        .map(|chapter| (chapter.start_offset, chapter.end_offset))
+        .collect();
+    std::fs::remove_file(&dst_path).unwrap();
+
+    assert_eq!(chapters_read, chapters);
+    // Missing offsets are written as all ones rather than zero.
+    assert_eq!(offsets, vec![(0, 145_000), (u32::MAX, u32::MAX)]);
+}
+
+#[cfg(feature = "mp3")]
+#[test]
+fn test_mp3_out_of_range_times() {
+    struct Test {
+        chapter: Chapter,
+        expected: Result<(), String>,
+    }
+
+    let src_path = std::path::Path::new("tests/data/id3-chapters.jfk-rice-university-speech.mp3");
+    let dst_path = std::env::temp_dir().join("chapters-test-out-of-range-times.mp3");
+
+    let tests = vec![
+        Test {
+            chapter: Chapter {
+                start: chrono::Duration::days(50),
+                ..Default::default()
+            },
+            expected: Err(String::from(
+                "Chapter time of 4320000000 ms is outside the range of ID3 chapter frames (0 to 4294967295 ms)",
+            )),
+        },
+        Test {
+            chapter: Chapter {
+                start: chrono::Duration::zero(),
+                end: Some(chrono::Duration::seconds(-1)),
+                ..Default::default()
+            },
+            expected: Err(String::from(
+                "Chapter time of -1000 ms is outside the range of ID3 chapter frames (0 to 4294967295 ms)",
+            )),
+        },
+        Test {
+            chapter: Chapter {
+                start: chrono::Duration::milliseconds(u32::MAX as i64 - 1),
+                ..Default::default()
+            },
+            expected: Ok(()),
+        },
+    ];
+
+    for test in tests {
+        let result = chapters::to_mp3_file(src_path, dst_path.as_path(), &[test.chapter]);
+        assert_eq!(result, test.expected);
+        // Nothing is written on failure.
+        assert_eq!(dst_path.exists(), result.is_ok());
+        if dst_path.exists() {
+            std::fs::remove_file(&dst_path).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_srt_invalid_timestamps() {
+    struct Test {
+        srt: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            srt: "1\n00:00:00,000 --> 00:01:00,500\nIntro\n",
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::zero(),
+                end: Some(chrono::Duration::milliseconds(60_500)),
+                title: Some(String::from("Intro")),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            srt: "1\n00:00:00,000 --> 00:61:00,000\nIntro\n",
+            expected: Err(String::from("Invalid SRT timestamp `00:61:00,000`")),
+        },
+        Test {
+            srt: "1\n99999999999999:00:00,000 --> 00:01:00,000\nIntro\n",
+            expected: Err(String::from(
+                "Invalid SRT timestamp `99999999999999:00:00,000`",
+            )),
+        },
+        Test {
+            srt: "1\n2562047788015:12:55,807 --> 2562047788015:12:55,808\nIntro\n",
+            expected: Err(String::from(
+                "Invalid SRT timestamp `2562047788015:12:55,808`",
+            )),
+        },
+        Test {
+            srt: "1\n00:00:-5,000 --> 00:01:00,000\nIntro\n",
+            expected: Err(String::from("Invalid SRT timestamp `00:00:-5,000`")),
+        },
+        Test {
+            srt: "1\n-1:00:00,000 --> 00:01:00,000\nIntro\n",
+            expected: Err(String::from("Invalid SRT timestamp `-1:00:00,000`")),
+        },
+        Test {
+            srt: "1\n00:00:00,-500 --> 00:01:00,000\nIntro\n",
+            expected: Err(String::from("Invalid SRT timestamp `00:00:00,-500`")),
+        },
+        Test {
+            srt: "1\n00:00:+5,000 --> 00:01:00,000\nIntro\n",
+            expected: Err(String::from("Invalid SRT timestamp `00:00:+5,000`")),
+        },
+    ];
+
+    for test in tests {
+        assert_eq!(chapters::from_srt(test.srt.as_bytes()), test.expected);
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_from_bytes() {
+    struct Test {
+        data: &'static [u8],
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            data: include_bytes!("data/podcast-namespace-chapters.bom.json"),
+            expected: chapters::from_json(
+                &include_bytes!("data/podcast-namespace-chapters.bom.json")[..],
+            ),
+        },
+        Test {
+            data: include_bytes!("data/description-chapters.txt"),
+            expected: chapters::from_description(include_str!("data/description-chapters.txt")),
+        },
+        Test {
+            data: b"WEBVTT\n\n00:00.000 --> 00:30.000\nIntro\n\n00:30.000 --> 01:00.000\nOutro\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::zero(),
+                    end: Some(chrono::Duration::seconds(30)),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::seconds(30),
+                    end: Some(chrono::Duration::minutes(1)),
+                    title: Some(String::from("Outro")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        // Not valid JSON, but readable as a description.
+        Test {
+            data: b"{Chapters}\n00:00 Intro\n01:00 Outro",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::zero(),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(1),
+                    title: Some(String::from("Outro")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            data: b"{\"chapters\": 1}",
+            expected: Err(String::from(
+                "invalid type: integer `1`, expected a sequence at line 1 column 14",
+            )),
+        },
+        Test {
+            data: b"\xff\xfe",
+            expected: Err(String::from(
+                "Input is not valid UTF-8: invalid utf-8 sequence of 1 bytes from index 0",
+            )),
+        },
+    ];
+
+    for test in tests {
+        assert_eq!(chapters::from_bytes(test.data), test.expected);
+    }
+}
+
+#[test]
+fn test_ffmetadata() {
+    struct Test {
+        ffmetadata: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            ffmetadata: ";FFMETADATA1\ntitle=Episode\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=1500\ntitle=Intro \\= hello\n\n[CHAPTER]\nTIMEBASE=1/10\nSTART=15\nEND=15\ntitle=Two\\\nlines\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::zero(),
+                    end: Some(chrono::Duration::milliseconds(1500)),
+                    title: Some(String::from("Intro = hello")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::milliseconds(1500),
+                    title: Some(String::from("Two\nlines")),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            ffmetadata: "[CHAPTER]\nSTART=0\n",
+            expected: Err(String::from(
+                "FFmetadata file does not start with `;FFMETADATA1`",
+            )),
+        },
+        Test {
+            ffmetadata: ";FFMETADATA1\n[CHAPTER]\nEND=10\n",
+            expected: Err(String::from("Chapter 0 has no START")),
+        },
+        Test {
+            ffmetadata: ";FFMETADATA1\n[CHAPTER]\nTIMEBASE=1/0\nSTART=0\n",
+            expected: Err(String::from("Invalid TIMEBASE `1/0`")),
+        },
+    ];
+
+    for test in tests {
+        let chapters = chapters::from_ffmetadata(test.ffmetadata.as_bytes());
+        assert_eq!(chapters, test.expected);
+
+        if let Ok(chapters) = chapters {
+            let ffmetadata = chapters::write(&chapters, chapters::ChapterFormat::FfMetadata)
+                .expect("Failed to write FFmetadata");
+            let read_back =
+                chapters::read(ffmetadata.as_bytes(), chapters::ChapterFormat::FfMetadata);
+            assert_eq!(read_back, Ok(chapters));
+        }
+    }
+
+    assert!(chapters::write(&[], chapters::ChapterFormat::Cue).is_err());
+}
+
+proptest::proptest! {
+    #[test]
+    fn test_description_never_panics(description in "\\PC*") {
+        let _ = chapters::from_description(&description);
+        let _ = chapters::from_description_verbose(&description);
+    }
+
+    // Lines that look like chapters, including huge and out-of-range timestamps.
+    #[test]
+    fn test_description_timestamps_never_panic(
+        description in "(([0-9]{1,20}:)?\\(?[0-9]{1,20}:[0-9]{2}(\\.[0-9]{1,3})?\\)?[ :\\-|]{1,2}[^\n]{1,10}\n){0,8}",
+        allow_long_minutes: bool,
+        allow_out_of_range: bool,
+        allow_fractional_seconds: bool,
+        require_contiguous: bool,
+        min_chapters in 0usize..4,
+    ) {
+        let options = chapters::DescriptionOptions {
+            allow_long_minutes,
+            allow_out_of_range,
+            allow_fractional_seconds,
+            require_contiguous,
+            min_chapters,
+        };
+        let _ = chapters::from_description_with_options(&description, &options);
+        let _ = chapters::parse_timestamp(&description);
+    }
+
+    #[test]
+    fn test_from_bytes_never_panics(prefix in "(WEBVTT\n\n|\\{)?", data in "\\PC*") {
+        let _ = chapters::from_bytes(format!("{}{}", prefix, data).as_bytes());
+    }
+
+    // WebVTT cues, including huge and out-of-range timestamps.
+    #[test]
+    fn test_from_bytes_cues_never_panic(
+        cues in "(([0-9]{1,20}:)?[0-9]{1,3}:[0-9]{2}\\.[0-9]{3} --> ([0-9]{1,20}:)?[0-9]{1,3}:[0-9]{2}\\.[0-9]{3}\n[^\n]{0,10}\n\n){0,4}",
+    ) {
+        let _ = chapters::from_bytes(format!("WEBVTT\n\n{}", cues).as_bytes());
+    }
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml() {
+    struct Test {
+        toml: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            toml: "",
+            expected: Ok(vec![]),
+        },
+        Test {
+            toml: r#"
+[[chapters]]
+start = 12.25
+img = "https://example.com/image.jpg"
+
+[[chapters]]
+start = 60
+url = "not a url"
+"#,
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::milliseconds(12_250),
+                    image: Some(Image::from(
+                        url::Url::parse("https://example.com/image.jpg").unwrap(),
+                    )),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::minutes(1),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            toml: "[[chapters]]\nstart = nan\n",
+            expected: Err(String::from(
+                "Error parsing TOML: TOML parse error at line 2, column 9\n  |\n2 | start = nan\n  |         ^^^\ninvalid time NaN, expected a non-negative number of seconds\n",
+            )),
+        },
+        Test {
+            toml: "[[chapters]]\ntitle = \"Intro\"\n",
+            expected: Err(String::from(
+                "Error parsing TOML: TOML parse error at line 1, column 1\n  |\n1 | [[chapters]]\n  | ^^^^^^^^^^^^\nmissing field `start`\n",
+            )),
+        },
+    ];
+
+    for test in tests {
+        assert_eq!(chapters::from_toml(test.toml), test.expected);
+    }
+}
+
+#[test]
+fn test_audacity_labels() {
+    struct Test {
+        labels: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        // Spectral selections are on their own lines after the labels.
+        Test {
+            labels: "1.5\t2.0004\tIntro\n\\\t100.000000\t2000.000000\n2.5\t2.5\t\n",
+            expected: Ok(vec![
+                Chapter {
+                    start: chrono::Duration::milliseconds(1500),
+                    end: Some(chrono::Duration::seconds(2)),
+                    title: Some(String::from("Intro")),
+                    ..Default::default()
+                },
+                Chapter {
+                    start: chrono::Duration::milliseconds(2500),
+                    ..Default::default()
+                },
+            ]),
+        },
+        Test {
+            labels: "1,5\t2,0\tIntro\n",
+            expected: Err(String::from("Invalid time `1,5`")),
+        },
+        Test {
+            labels: "NaN\t1.0\tIntro\n",
+            expected: Err(String::from("Invalid time `NaN`")),
+        },
+        Test {
+            labels: "2.0\t1.0\tIntro\n",
+            expected: Err(String::from(
+                "Label line `2.0\t1.0\tIntro` ends before it starts",
+            )),
+        },
+        Test {
+            labels: "2.0 Intro\n",
+            expected: Err(String::from("Label line `2.0 Intro` has too few columns")),
+        },
+    ];
+
+    for test in tests {
+        assert_eq!(
+            chapters::from_audacity_labels(test.labels.as_bytes()),
+            test.expected
+        );
+    }
+}
+
+#[test]
+fn test_audacity_labels_round_trip() {
+    let chapters = vec![
+        Chapter {
+            start: chrono::Duration::milliseconds(1),
+            end: Some(chrono::Duration::hours(2) + chrono::Duration::milliseconds(999)),
+            title: Some(String::from("Intro")),
+            ..Default::default()
+        },
+        Chapter {
+            start: chrono::Duration::hours(3),
+            ..Default::default()
+        },
+    ];
+
+    let labels = chapters::to_audacity_labels(&chapters).unwrap();
+
+    assert_eq!(labels, "0.001\t7200.999\tIntro\n10800.000\t10800.000\t\n");
+    assert_eq!(
+        chapters::from_audacity_labels(labels.as_bytes()),
+        Ok(chapters)
+    );
+    assert_eq!(
+        chapters::to_audacity_labels(&[Chapter {
+            start: chrono::Duration::seconds(-1),
+            ..Default::default()
+        }]),
+        Err(String::from("Chapter 0 has a negative time"))
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_invalid_times() {
+    struct Test {
+        json: &'static str,
+        expected: Result<Vec<Chapter>, String>,
+    }
+
+    let tests = vec![
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": -1}]}"#,
+            expected: Err(String::from(
+                "invalid time -1.0, expected a non-negative number of seconds at line 1 column 51",
+            )),
+        },
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": 0, "endTime": -0.5}]}"#,
+            expected: Err(String::from(
+                "invalid time -0.5, expected a non-negative number of seconds at line 1 column 67",
+            )),
+        },
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": 1e300}]}"#,
+            expected: Err(String::from(
+                "invalid time 1e300, expected a non-negative number of seconds at line 1 column 54",
+            )),
+        },
+        // JSON has no NaN, so it is a syntax error.
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": NaN}]}"#,
+            expected: Err(String::from("expected value at line 1 column 49")),
+        },
+        // Some tools quote the numbers.
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": " 30.5", "endTime": "60"}]}"#,
+            expected: Ok(vec![Chapter {
+                start: chrono::Duration::milliseconds(30_500),
+                end: Some(chrono::Duration::minutes(1)),
+                ..Default::default()
+            }]),
+        },
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": "half past"}]}"#,
+            expected: Err(String::from(
+                "invalid time `half past`, expected a number of seconds at line 1 column 59",
+            )),
+        },
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": 0, "endTime": "abc"}]}"#,
+            expected: Err(String::from(
+                "invalid time `abc`, expected a number of seconds at line 1 column 67",
+            )),
+        },
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": 0, "endTime": null}]}"#,
+            expected: Ok(vec![Chapter::default()]),
+        },
+        Test {
+            json: r#"{"version": "1.2.0", "chapters": [{"startTime": "-1"}]}"#,
+            expected: Err(String::from(
+                "invalid time -1.0, expected a non-negative number of seconds at line 1 column 53",
+            )),
+        },
+    ];
+
+    for test in tests {
+        assert_eq!(from_json(test.json.as_bytes()), test.expected);
+    }
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_write_json_error() {
+    struct FailingWriter;
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    assert_eq!(
+        chapters::write_json(FailingWriter, &[Chapter::at_seconds(0.0, "Intro")]),
+        Err(String::from("Error writing JSON: disk full"))
+    );
 }